@@ -1,15 +1,51 @@
 use anyhow::{anyhow, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
+
+/// Model names known at release time. Newer models work too; anything else
+/// only earns a warning so typos show up before the first spell fails.
+const KNOWN_MODELS: &[&str] = &[
+    "claude-3-haiku-20240307",
+    "claude-3-sonnet-20240229",
+    "claude-3-opus-20240229",
+    "claude-3-opus-latest",
+    "claude-3-5-haiku-20241022",
+    "claude-3-5-haiku-latest",
+    "claude-3-5-sonnet-20240620",
+    "claude-3-5-sonnet-20241022",
+    "claude-3-5-sonnet-latest",
+    "claude-3-7-sonnet-20250219",
+    "claude-3-7-sonnet-latest",
+    "claude-sonnet-4-20250514",
+    "claude-opus-4-20250514",
+];
 
 #[derive(Debug, Serialize)]
 struct ClaudeRequest {
     model: String,
     max_tokens: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
     messages: Vec<Message>,
 }
 
+/// Who authored a turn of the conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct Message {
     role: String,
@@ -19,6 +55,28 @@ struct Message {
 #[derive(Debug, Deserialize)]
 struct ClaudeResponse {
     content: Vec<Content>,
+    #[serde(default)]
+    usage: Usage,
+    /// Why Claude stopped, e.g. "end_turn" or "max_tokens"
+    #[serde(default)]
+    stop_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Usage {
+    input_tokens: u32,
+    output_tokens: u32,
+}
+
+/// Claude's answer to a message, with the tokens it cost.
+pub struct Reply {
+    pub text: String,
+    /// The model name sent to the API
+    pub model: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    /// Claude hit the token limit, so the text ends mid-reply
+    pub truncated: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,6 +87,13 @@ struct Content {
 pub struct ClaudeClient {
     client: Client,
     api_key: String,
+    messages_url: String,
+    model: String,
+    max_tokens: i32,
+    /// Log full request and response bodies at debug level (`CLAUDE_LOG_BODIES`)
+    log_bodies: bool,
+    /// Most prior turns replayed with each message (`MAX_REPLAY_TURNS`)
+    max_replay_turns: Option<usize>,
 }
 
 impl ClaudeClient {
@@ -49,13 +114,109 @@ impl ClaudeClient {
                 })
         };
 
+        let model = std::env::var("CLAUDE_MODEL")
+            .unwrap_or_else(|_| "claude-3-5-sonnet-20241022".to_string());
+        if !KNOWN_MODELS.contains(&model.as_str()) {
+            warn!(
+                "Unrecognized CLAUDE_MODEL {}; if this is a typo, spells will fail. Known models: {}",
+                model,
+                KNOWN_MODELS.join(", ")
+            );
+        }
+
+        // Allow routing through a proxy or gateway instead of the public API
+        let base_url = std::env::var("ANTHROPIC_BASE_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| "https://api.anthropic.com".to_string());
+        let messages_url = format!("{}/v1/messages", base_url.trim().trim_end_matches('/'));
+
         Self {
             client: Client::new(),
             api_key: api_key.trim().to_string(),
+            messages_url,
+            model,
+            max_tokens: 1024,
+            log_bodies: std::env::var("CLAUDE_LOG_BODIES").is_ok_and(|v| !v.is_empty() && v != "0"),
+            max_replay_turns: std::env::var("MAX_REPLAY_TURNS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0),
         }
     }
 
-    pub async fn send_message(&self, message: &str) -> Result<String> {
+    /// Scrub the API key from anything about to be logged.
+    fn redact(&self, text: &str) -> String {
+        if self.api_key.is_empty() {
+            text.to_string()
+        } else {
+            text.replace(&self.api_key, "[REDACTED]")
+        }
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn max_tokens(&self) -> i32 {
+        self.max_tokens
+    }
+
+    pub fn has_api_key(&self) -> bool {
+        !self.api_key.is_empty()
+    }
+
+    /// Ask Claude to condense earlier turns into a summary that can stand in for them.
+    pub async fn summarize(&self, turns: &[(Role, String)]) -> Result<String> {
+        let transcript = turns
+            .iter()
+            .map(|(role, content)| format!("{}: {}", role.as_str(), content))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let prompt = format!(
+            "Summarize the following conversation so it can replace the original \
+             turns as context. Keep facts, decisions and open questions; drop pleasantries.\n\n\
+             {transcript}"
+        );
+
+        self.send_message(&[], &prompt, None, None)
+            .await
+            .map(|reply| reply.text)
+    }
+
+    /// The most recent turns to replay, capped at `MAX_REPLAY_TURNS`. The window
+    /// never opens on an assistant turn, so user/assistant pairs stay whole.
+    fn replay_window<'a>(&self, history: &'a [(Role, String)]) -> &'a [(Role, String)] {
+        let Some(max) = self.max_replay_turns else {
+            return history;
+        };
+        if history.len() <= max {
+            return history;
+        }
+
+        let mut start = history.len() - max;
+        while history
+            .get(start)
+            .is_some_and(|(role, _)| *role == Role::Assistant)
+        {
+            start += 1;
+        }
+        debug!(
+            "Replaying {} of {} turns",
+            history.len() - start,
+            history.len()
+        );
+        &history[start..]
+    }
+
+    /// Send `message` to Claude, replaying the prior turns of the conversation first.
+    pub async fn send_message(
+        &self,
+        history: &[(Role, String)],
+        message: &str,
+        model: Option<&str>,
+        system: Option<&str>,
+    ) -> Result<Reply> {
         debug!("Sending message to Claude: {}", message);
 
         if self.api_key.is_empty() {
@@ -63,17 +224,32 @@ impl ClaudeClient {
         }
 
         let request = ClaudeRequest {
-            model: "claude-3-5-sonnet-20241022".to_string(),
-            max_tokens: 1024,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: message.to_string(),
-            }],
+            model: model.unwrap_or(&self.model).to_string(),
+            max_tokens: self.max_tokens,
+            system: system.map(str::to_string),
+            messages: self
+                .replay_window(history)
+                .iter()
+                .map(|(role, content)| Message {
+                    role: role.as_str().to_string(),
+                    content: content.clone(),
+                })
+                .chain(std::iter::once(Message {
+                    role: Role::User.as_str().to_string(),
+                    content: message.to_string(),
+                }))
+                .collect(),
         };
+        if self.log_bodies {
+            debug!(
+                "Claude request body: {}",
+                self.redact(&serde_json::to_string(&request)?)
+            );
+        }
 
         let response = self
             .client
-            .post("https://api.anthropic.com/v1/messages")
+            .post(&self.messages_url)
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", "2023-06-01")
             .header("content-type", "application/json")
@@ -81,19 +257,38 @@ impl ClaudeClient {
             .send()
             .await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if self.log_bodies {
+            debug!("Claude response body ({}): {}", status, self.redact(&body));
+        }
+
+        if !status.is_success() {
+            let error_text = self.redact(&body);
             error!("Claude API error: {}", error_text);
             return Err(anyhow!("Claude API error: {}", error_text));
         }
 
-        let claude_response: ClaudeResponse = response.json().await?;
+        let claude_response: ClaudeResponse = serde_json::from_str(&body)?;
+        let truncated = claude_response.stop_reason.as_deref() == Some("max_tokens");
+        if truncated {
+            warn!(
+                "Reply stopped at the {} token limit and is incomplete",
+                request.max_tokens
+            );
+        }
 
-        Ok(claude_response
-            .content
-            .into_iter()
-            .map(|c| c.text)
-            .collect::<Vec<_>>()
-            .join("\n"))
+        Ok(Reply {
+            model: request.model,
+            text: claude_response
+                .content
+                .into_iter()
+                .map(|c| c.text)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            input_tokens: claude_response.usage.input_tokens,
+            output_tokens: claude_response.usage.output_tokens,
+            truncated,
+        })
     }
 }
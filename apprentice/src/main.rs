@@ -1,20 +1,49 @@
 mod claude;
+mod prompt;
+mod rate_limit;
 mod server;
+mod text;
 
 use anyhow::Result;
 use std::net::SocketAddr;
-use tonic::transport::Server;
-use tracing::{error, info};
+use tonic::service::Interceptor;
+use tonic::transport::{Identity, Server, ServerTlsConfig};
+use tonic::{Request, Status};
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// Rejects requests whose bearer token doesn't match `APPRENTICE_TOKEN`.
+#[derive(Clone)]
+struct AuthCheck {
+    expected: Option<String>,
+}
+
+impl Interceptor for AuthCheck {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let Some(expected) = &self.expected else {
+            return Ok(request);
+        };
+
+        match request.metadata().get("authorization") {
+            Some(token) if token.as_bytes() == expected.as_bytes() => Ok(request),
+            Some(_) => Err(Status::unauthenticated("Invalid auth token")),
+            None => Err(Status::unauthenticated("Missing auth token")),
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG").unwrap_or_else(|_| "apprentice=info".into()),
-        ))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let registry = tracing_subscriber::registry().with(tracing_subscriber::EnvFilter::new(
+        std::env::var("RUST_LOG").unwrap_or_else(|_| "apprentice=info".into()),
+    ));
+    if std::env::var("LOG_FORMAT").is_ok_and(|f| f == "json") {
+        registry
+            .with(tracing_subscriber::fmt::layer().json())
+            .init();
+    } else {
+        registry.with(tracing_subscriber::fmt::layer()).init();
+    }
 
     let apprentice_name =
         std::env::var("APPRENTICE_NAME").unwrap_or_else(|_| "unnamed".to_string());
@@ -31,7 +60,52 @@ async fn main() -> Result<()> {
 
     info!("Creating apprentice server...");
     let apprentice = server::ApprenticeServer::new(apprentice_name);
-    let apprentice_service = server::spells::apprentice_server::ApprenticeServer::new(apprentice);
+
+    // Re-read SYSTEM_PROMPT_PATH on SIGHUP so operators can update the prompt in place.
+    // Without a readable file the current prompt, possibly set over gRPC, is kept.
+    let state = apprentice.state();
+    let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            let Ok(path) = std::env::var("SYSTEM_PROMPT_PATH") else {
+                info!(
+                    "Received SIGHUP, but SYSTEM_PROMPT_PATH is not set; keeping the system prompt"
+                );
+                continue;
+            };
+            match std::fs::read_to_string(&path) {
+                Ok(base) => {
+                    let prompt = server::compose_system_prompt(Some(base));
+                    info!(
+                        "Received SIGHUP, system prompt {}",
+                        if prompt.is_some() {
+                            "reloaded"
+                        } else {
+                            "cleared"
+                        }
+                    );
+                    state.lock().await.set_system_prompt(prompt);
+                }
+                Err(e) => warn!(
+                    "Received SIGHUP, but failed to read system prompt {}: {}; keeping the current one",
+                    path, e
+                ),
+            }
+        }
+    });
+    let expected_token = std::env::var("APPRENTICE_TOKEN")
+        .ok()
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("Bearer {t}"));
+    if expected_token.is_some() {
+        info!("Requiring bearer token on incoming requests");
+    }
+    let apprentice_service = server::spells::apprentice_server::ApprenticeServer::with_interceptor(
+        apprentice,
+        AuthCheck {
+            expected: expected_token,
+        },
+    );
 
     info!("Starting gRPC server...");
 
@@ -46,7 +120,27 @@ async fn main() -> Result<()> {
         let _ = shutdown_tx.send(());
     });
 
-    Server::builder()
+    // Like the sorcerer, refuse a half-configured TLS setup rather than
+    // quietly serving plaintext
+    let tls = match (
+        std::env::var("APPRENTICE_TLS_CERT").ok(),
+        std::env::var("APPRENTICE_TLS_KEY").ok(),
+    ) {
+        (Some(cert_path), Some(key_path)) => Some((cert_path, key_path)),
+        (None, None) => None,
+        _ => anyhow::bail!("APPRENTICE_TLS_CERT and APPRENTICE_TLS_KEY must be set together"),
+    };
+
+    let mut builder = Server::builder();
+    if let Some((cert_path, key_path)) = tls {
+        info!("Enabling TLS with certificate {}", cert_path);
+        let cert = std::fs::read_to_string(&cert_path)?;
+        let key = std::fs::read_to_string(&key_path)?;
+        builder =
+            builder.tls_config(ServerTlsConfig::new().identity(Identity::from_pem(cert, key)))?;
+    }
+
+    builder
         .add_service(apprentice_service)
         .serve_with_shutdown(addr, async {
             shutdown_rx.await.ok();
@@ -0,0 +1,39 @@
+/// Values substituted into a system prompt before it is sent to Claude.
+pub struct PromptVars<'a> {
+    pub name: &'a str,
+    pub date: &'a str,
+    pub workspace: &'a str,
+}
+
+/// Replace `{{name}}`, `{{date}}` and `{{workspace}}` in a system prompt.
+/// Any other `{{...}}` is left untouched, and substituted values are never
+/// themselves expanded.
+pub fn render(template: &str, vars: &PromptVars) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = placeholder.find("}}").and_then(|end| {
+            let value = match &placeholder[2..end] {
+                "name" => vars.name,
+                "date" => vars.date,
+                "workspace" => vars.workspace,
+                _ => return None,
+            };
+            Some((value, end + 2))
+        });
+        match value {
+            Some((value, len)) => {
+                rendered.push_str(value);
+                rest = &placeholder[len..];
+            }
+            None => {
+                rendered.push_str("{{");
+                rest = &placeholder[2..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
@@ -0,0 +1,52 @@
+use std::time::Instant;
+
+/// A token bucket that refills continuously up to `capacity` spells per minute.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn per_minute(capacity: u32) -> Self {
+        Self {
+            capacity: capacity as f64,
+            tokens: capacity as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Build a limiter from `SPELL_RATE_LIMIT`, or `None` when unset or zero.
+    pub fn from_env() -> Option<Self> {
+        std::env::var("SPELL_RATE_LIMIT")
+            .ok()
+            .and_then(|limit| limit.parse::<u32>().ok())
+            .filter(|limit| *limit > 0)
+            .map(Self::per_minute)
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.capacity / 60.0).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Take one token if available.
+    pub fn try_acquire(&mut self) -> bool {
+        self.refill();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whole spells that could be cast right now.
+    pub fn remaining(&mut self) -> u32 {
+        self.refill();
+        self.tokens.floor() as u32
+    }
+}
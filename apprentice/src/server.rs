@@ -1,9 +1,13 @@
-use crate::claude::ClaudeClient;
+use crate::claude::{ClaudeClient, Role};
+use crate::prompt::{self, PromptVars};
+use crate::rate_limit::RateLimiter;
+use crate::text::truncate_on_char_boundary;
 use anyhow::Result;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
 use tonic::{Request, Response, Status};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 pub mod spells {
     tonic::include_proto!("spells");
@@ -11,41 +15,319 @@ pub mod spells {
 
 use spells::apprentice_server::Apprentice;
 use spells::{
-    ChatHistoryRequest, ChatHistoryResponse, KillRequest, KillResponse, SpellRequest,
+    AppendHistoryRequest, AppendHistoryResponse, CancelSpellRequest, CancelSpellResponse,
+    ChatHistoryRequest, ChatHistoryResponse, ConfigRequest, ConfigResponse, DrainRequest,
+    DrainResponse, GetSystemPromptRequest, GetSystemPromptResponse, HealthRequest, HealthResponse,
+    HistoryTurn, IdentityRequest, IdentityResponse, KillRequest, KillResponse,
+    LastIncantationRequest, LastIncantationResponse, ListSessionsRequest, ListSessionsResponse,
+    PingRequest, PingResponse, SetSystemPromptRequest, SetSystemPromptResponse, SpellRequest,
     SpellResponse, StatusRequest, StatusResponse,
 };
 
+const DEFAULT_SESSION: &str = "default";
+
+/// Turns kept per session (50 exchanges); older ones are dropped.
+const MAX_HISTORY_TURNS: usize = 100;
+
+/// One turn of a session's chat history. Turns are stored and sent
+/// structured; the sorcerer renders them as text.
+#[derive(Debug, Clone, PartialEq)]
+struct HistoryEntry {
+    role: Role,
+    content: String,
+    /// RFC 3339 time the turn was recorded
+    timestamp: String,
+}
+
+impl HistoryEntry {
+    fn new(role: Role, content: String) -> Self {
+        Self {
+            role,
+            content,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Drop the oldest turns beyond `MAX_HISTORY_TURNS`, then any leading
+/// assistant turn, so a session's history always opens with a user turn.
+fn trim_history(history: &mut Vec<HistoryEntry>) {
+    let excess = history.len().saturating_sub(MAX_HISTORY_TURNS);
+    let orphaned = history[excess..]
+        .iter()
+        .take_while(|entry| entry.role == Role::Assistant)
+        .count();
+    history.drain(0..excess + orphaned);
+}
+
+/// The role/content pairs Claude is sent, without timestamps.
+fn replay_turns(entries: &[HistoryEntry]) -> Vec<(Role, String)> {
+    entries
+        .iter()
+        .map(|entry| (entry.role, entry.content.clone()))
+        .collect()
+}
+
+/// Cut a reply down to `max` characters, noting how much was dropped.
+pub(crate) fn truncate_response(response: String, max: usize) -> String {
+    let kept = truncate_on_char_boundary(&response, max);
+    if kept.len() == response.len() {
+        return response;
+    }
+    let dropped = response[kept.len()..].chars().count();
+    format!("{}…[truncated {} chars]", kept, dropped)
+}
+
+/// Read the system prompt from `SYSTEM_PROMPT_PATH`, if one is configured, and
+/// add any `SYSTEM_PROMPT_APPEND` instructions after it.
+pub fn load_system_prompt() -> Option<String> {
+    let base = std::env::var("SYSTEM_PROMPT_PATH").ok().and_then(|path| {
+        match std::fs::read_to_string(&path) {
+            Ok(prompt) => Some(prompt),
+            Err(e) => {
+                warn!("Failed to read system prompt {}: {}", path, e);
+                None
+            }
+        }
+    });
+    compose_system_prompt(base)
+}
+
+/// Combine a base prompt with any `SYSTEM_PROMPT_APPEND` instructions.
+pub fn compose_system_prompt(base: Option<String>) -> Option<String> {
+    let base = base.filter(|p| !p.trim().is_empty());
+    let extra = std::env::var("SYSTEM_PROMPT_APPEND")
+        .ok()
+        .filter(|p| !p.trim().is_empty());
+
+    match (base, extra) {
+        (Some(base), Some(extra)) => Some(format!("{}\n\n{}", base.trim_end(), extra)),
+        (base, extra) => base.or(extra),
+    }
+}
+
+/// Fill in a system prompt's placeholders for the spell about to be cast, so
+/// `{{date}}` stays current for long-lived apprentices.
+fn render_system_prompt(template: &str, name: &str) -> String {
+    let workspace = std::env::var("WORKSPACE")
+        .ok()
+        .or_else(|| {
+            std::env::current_dir()
+                .ok()
+                .map(|dir| dir.display().to_string())
+        })
+        .unwrap_or_default();
+    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    prompt::render(
+        template,
+        &PromptVars {
+            name,
+            date: &date,
+            workspace: &workspace,
+        },
+    )
+}
+
+fn session_key(session_id: &str) -> String {
+    if session_id.is_empty() {
+        DEFAULT_SESSION.to_string()
+    } else {
+        session_id.to_string()
+    }
+}
+
+/// What an apprentice is doing, reported to the sorcerer by `GetStatus`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApprenticeStatus {
+    Idle,
+    Casting,
+    /// The last spell failed, with the reason it did
+    Error(String),
+}
+
+impl ApprenticeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprenticeStatus::Idle => "idle",
+            ApprenticeStatus::Casting => "casting",
+            ApprenticeStatus::Error(_) => "error",
+        }
+    }
+}
+
+/// Summarize the oldest turns of a session once it grows past `threshold`,
+/// keeping the most recent `keep` turns verbatim.
+#[derive(Debug, Clone, Copy)]
+struct Compaction {
+    threshold: usize,
+    keep: usize,
+}
+
+impl Compaction {
+    /// Read `AGENT_COMPACT_HISTORY` and its tuning variables, or `None` when disabled.
+    fn from_env() -> Option<Self> {
+        let enabled =
+            std::env::var("AGENT_COMPACT_HISTORY").is_ok_and(|v| !v.is_empty() && v != "0");
+        if !enabled {
+            return None;
+        }
+
+        let threshold = std::env::var("AGENT_COMPACT_THRESHOLD")
+            .ok()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(40usize);
+        let keep = std::env::var("AGENT_COMPACT_KEEP")
+            .ok()
+            .and_then(|k| k.parse().ok())
+            .unwrap_or(10usize);
+
+        // Turns come in user/assistant pairs, so keep an even number of them
+        let keep = keep.min(threshold.saturating_sub(2)) & !1;
+        Some(Self { threshold, keep })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ApprenticeState {
     name: String,
-    state: String,
+    status: ApprenticeStatus,
     spells_cast: i32,
     last_spell_time: Option<String>,
-    chat_history: Vec<String>,
+    rate_limiter: Option<RateLimiter>,
+    chat_history: HashMap<String, Vec<HistoryEntry>>,
+    /// Most recent incantation per session, kept even when the spell failed
+    last_incantation: HashMap<String, String>,
+    /// Sent as Claude's system prompt; replaceable at runtime via `SetSystemPrompt`
+    system_prompt: Option<String>,
+    /// Signalled by `CancelSpell` to abort the spell currently being cast
+    current_spell: Option<Arc<Notify>>,
+    /// Spells accepted and not yet finished; the status is idle only at zero
+    in_flight: u32,
+    /// Why the most recently finished spell failed, reported once none are in flight
+    last_error: Option<String>,
+    /// Set by `Drain`; new spells are refused while the current one finishes
+    draining: bool,
+}
+
+impl ApprenticeState {
+    pub fn set_system_prompt(&mut self, prompt: Option<String>) {
+        self.system_prompt = prompt;
+    }
+
+    /// Records the end of a spell, with its error if it failed. The cancel
+    /// handle is only released if it is still this spell's, so a newer spell
+    /// stays cancellable.
+    fn finish_spell(&mut self, cancel: &Arc<Notify>, error: Option<String>) {
+        self.in_flight = self.in_flight.saturating_sub(1);
+        if self
+            .current_spell
+            .as_ref()
+            .is_some_and(|current| Arc::ptr_eq(current, cancel))
+        {
+            self.current_spell = None;
+        }
+        self.last_error = error;
+        self.status = match (&self.last_error, self.in_flight) {
+            (_, 1..) => ApprenticeStatus::Casting,
+            (Some(error), 0) => ApprenticeStatus::Error(error.clone()),
+            (None, 0) => ApprenticeStatus::Idle,
+        };
+    }
 }
 
 pub struct ApprenticeServer {
     state: Arc<Mutex<ApprenticeState>>,
     claude_client: Arc<ClaudeClient>,
+    max_response_chars: Option<usize>,
+    compaction: Option<Compaction>,
 }
 
 impl ApprenticeServer {
     pub fn new(name: String) -> Self {
-        let state = Arc::new(Mutex::new(ApprenticeState {
+        let state = ApprenticeState {
             name: name.clone(),
-            state: "idle".to_string(),
+            status: ApprenticeStatus::Idle,
             spells_cast: 0,
             last_spell_time: None,
-            chat_history: Vec::new(),
-        }));
+            rate_limiter: RateLimiter::from_env(),
+            chat_history: HashMap::new(),
+            last_incantation: HashMap::new(),
+            system_prompt: load_system_prompt(),
+            current_spell: None,
+            in_flight: 0,
+            last_error: None,
+            draining: false,
+        };
 
         let claude_client = Arc::new(ClaudeClient::new());
+        let state = Arc::new(Mutex::new(state));
+
+        let max_response_chars = std::env::var("MAX_RESPONSE_CHARS")
+            .ok()
+            .and_then(|max| max.parse().ok())
+            .filter(|max| *max > 0);
 
         Self {
             state,
             claude_client,
+            max_response_chars,
+            compaction: Compaction::from_env(),
         }
     }
+
+    /// Shared handle on the apprentice's state, for tasks outside the gRPC service.
+    pub fn state(&self) -> Arc<Mutex<ApprenticeState>> {
+        self.state.clone()
+    }
+
+    /// Replace a session's oldest turns with a summary when compaction is on
+    /// and the history has grown too long. Falls back to the full history if
+    /// summarizing fails.
+    async fn compact_history(
+        &self,
+        session: &str,
+        prior_turns: Vec<HistoryEntry>,
+    ) -> Vec<HistoryEntry> {
+        let Some(compaction) = self.compaction else {
+            return prior_turns;
+        };
+        if prior_turns.len() <= compaction.threshold {
+            return prior_turns;
+        }
+
+        let split = prior_turns.len() - compaction.keep;
+        info!("Compacting {} turns of session {}", split, session);
+        let summary = match self
+            .claude_client
+            .summarize(&replay_turns(&prior_turns[..split]))
+            .await
+        {
+            Ok(summary) => summary,
+            Err(e) => {
+                warn!("History compaction failed, sending the full history: {}", e);
+                return prior_turns;
+            }
+        };
+
+        let mut compacted = vec![
+            HistoryEntry::new(
+                Role::User,
+                format!("[Summary of the earlier conversation]\n{summary}"),
+            ),
+            HistoryEntry::new(Role::Assistant, "Understood.".to_string()),
+        ];
+        compacted.extend_from_slice(&prior_turns[split..]);
+
+        // Store the compacted form too, unless the session moved on meanwhile
+        let mut state = self.state.lock().await;
+        if let Some(history) = state.chat_history.get_mut(session) {
+            if history.as_slice() == prior_turns.as_slice() {
+                *history = compacted.clone();
+            }
+        }
+
+        compacted
+    }
 }
 
 #[tonic::async_trait]
@@ -57,50 +339,115 @@ impl Apprentice for ApprenticeServer {
         let spell = request.into_inner();
         info!("Casting spell {}: {}", spell.spell_id, spell.incantation);
 
-        {
+        let session = session_key(&spell.session_id);
+        let cancel = Arc::new(Notify::new());
+        let (prior_turns, system_prompt) = {
             let mut state = self.state.lock().await;
-            state.state = "casting".to_string();
-        }
+            if state.draining {
+                info!("Spell {} rejected while draining", spell.spell_id);
+                return Ok(Response::new(SpellResponse {
+                    spell_id: spell.spell_id,
+                    success: false,
+                    error: "draining: this apprentice is not accepting new spells".to_string(),
+                    ..Default::default()
+                }));
+            }
+            state
+                .last_incantation
+                .insert(session.clone(), spell.incantation.clone());
+            if let Some(limiter) = &mut state.rate_limiter {
+                if !limiter.try_acquire() {
+                    info!("Spell {} rejected by rate limit", spell.spell_id);
+                    return Ok(Response::new(SpellResponse {
+                        spell_id: spell.spell_id,
+                        result: String::new(),
+                        success: false,
+                        error: "Rate limit exceeded (SPELL_RATE_LIMIT spells per minute)"
+                            .to_string(),
+                        ..Default::default()
+                    }));
+                }
+            }
+            state.status = ApprenticeStatus::Casting;
+            state.current_spell = Some(cancel.clone());
+            state.in_flight += 1;
+            let prior_turns = state
+                .chat_history
+                .get(&session)
+                .cloned()
+                .unwrap_or_default();
+            let system_prompt = state
+                .system_prompt
+                .as_deref()
+                .map(|template| render_system_prompt(template, &state.name));
+            (prior_turns, system_prompt)
+        };
+        let prior_turns = replay_turns(&self.compact_history(&session, prior_turns).await);
+
+        let reply = tokio::select! {
+            reply = self
+                .claude_client
+                .send_message(
+                    &prior_turns,
+                    &spell.incantation,
+                    spell.model.as_deref(),
+                    system_prompt.as_deref(),
+                ) => Some(reply),
+            _ = cancel.notified() => None,
+        };
+
+        let Some(reply) = reply else {
+            info!("Spell {} cancelled", spell.spell_id);
+            self.state.lock().await.finish_spell(&cancel, None);
+            return Ok(Response::new(SpellResponse {
+                spell_id: spell.spell_id,
+                result: String::new(),
+                success: false,
+                error: "cancelled".to_string(),
+                ..Default::default()
+            }));
+        };
+
+        let result = match reply {
+            Ok(reply) => {
+                let response = match self.max_response_chars {
+                    Some(max) => truncate_response(reply.text, max),
+                    None => reply.text,
+                };
 
-        let result = match self.claude_client.send_message(&spell.incantation).await {
-            Ok(response) => {
                 let mut state = self.state.lock().await;
-                state.state = "idle".to_string();
+                state.finish_spell(&cancel, None);
                 state.spells_cast += 1;
                 state.last_spell_time = Some(chrono::Utc::now().to_rfc3339());
 
-                // Add to chat history
-                let apprentice_name = state.name.clone();
-                state
-                    .chat_history
-                    .push(format!("Sorcerer: {}", spell.incantation));
-                state
-                    .chat_history
-                    .push(format!("{}: {}", apprentice_name, response));
-
-                // Keep only last 50 exchanges (100 lines)
-                if state.chat_history.len() > 100 {
-                    let len = state.chat_history.len();
-                    state.chat_history.drain(0..len - 100);
-                }
+                // Add to the session's chat history
+                let history = state.chat_history.entry(session).or_default();
+                history.push(HistoryEntry::new(Role::User, spell.incantation.clone()));
+                history.push(HistoryEntry::new(Role::Assistant, response.clone()));
+                trim_history(history);
 
                 SpellResponse {
                     spell_id: spell.spell_id,
                     result: response.clone(),
                     success: true,
                     error: String::new(),
+                    input_tokens: reply.input_tokens,
+                    output_tokens: reply.output_tokens,
+                    model: reply.model,
+                    truncated: reply.truncated,
                 }
             }
             Err(e) => {
                 error!("Spell casting failed: {}", e);
                 let mut state = self.state.lock().await;
-                state.state = "error".to_string();
+                state.finish_spell(&cancel, Some(e.to_string()));
 
                 SpellResponse {
                     spell_id: spell.spell_id,
                     result: String::new(),
                     success: false,
                     error: e.to_string(),
+                    ..Default::default()
                 }
             }
         };
@@ -112,12 +459,23 @@ impl Apprentice for ApprenticeServer {
         &self,
         _request: Request<StatusRequest>,
     ) -> Result<Response<StatusResponse>, Status> {
-        let state = self.state.lock().await;
+        let mut state = self.state.lock().await;
+        let spell_budget = state
+            .rate_limiter
+            .as_mut()
+            .map(|limiter| limiter.remaining() as i32);
 
         Ok(Response::new(StatusResponse {
             apprentice_name: state.name.clone(),
-            state: state.state.clone(),
+            state: state.status.as_str().to_string(),
+            error: match &state.status {
+                ApprenticeStatus::Error(e) => e.clone(),
+                _ => String::new(),
+            },
             last_spell_time: state.last_spell_time.clone().unwrap_or_default(),
+            spell_budget,
+            draining: state.draining,
+            in_flight: state.in_flight,
         }))
     }
 
@@ -125,22 +483,31 @@ impl Apprentice for ApprenticeServer {
         &self,
         request: Request<ChatHistoryRequest>,
     ) -> Result<Response<ChatHistoryResponse>, Status> {
-        let lines = request.into_inner().lines as usize;
+        let request = request.into_inner();
+        let lines = request.lines as usize;
         let state = self.state.lock().await;
+        let chat_history = state
+            .chat_history
+            .get(&session_key(&request.session_id))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
 
         // Get the last n lines
-        let history = if lines == 0 {
-            state.chat_history.clone()
+        let start = if lines == 0 {
+            0
         } else {
-            let start = if state.chat_history.len() > lines {
-                state.chat_history.len() - lines
-            } else {
-                0
-            };
-            state.chat_history[start..].to_vec()
+            chat_history.len().saturating_sub(lines)
         };
+        let entries = chat_history[start..]
+            .iter()
+            .map(|entry| HistoryTurn {
+                role: entry.role.as_str().to_string(),
+                content: entry.content.clone(),
+                timestamp: entry.timestamp.clone(),
+            })
+            .collect();
 
-        Ok(Response::new(ChatHistoryResponse { history }))
+        Ok(Response::new(ChatHistoryResponse { entries }))
     }
 
     async fn kill(&self, request: Request<KillRequest>) -> Result<Response<KillResponse>, Status> {
@@ -157,4 +524,187 @@ impl Apprentice for ApprenticeServer {
             message: format!("Fading away into the ether... ({})", reason),
         }))
     }
+
+    async fn get_config(
+        &self,
+        _request: Request<ConfigRequest>,
+    ) -> Result<Response<ConfigResponse>, Status> {
+        let state = self.state.lock().await;
+        let history_len: usize = state.chat_history.values().map(Vec::len).sum();
+
+        Ok(Response::new(ConfigResponse {
+            model: self.claude_client.model().to_string(),
+            max_tokens: self.claude_client.max_tokens(),
+            system_prompt_present: state.system_prompt.is_some(),
+            history_len: history_len as i32,
+        }))
+    }
+
+    async fn list_sessions(
+        &self,
+        _request: Request<ListSessionsRequest>,
+    ) -> Result<Response<ListSessionsResponse>, Status> {
+        let state = self.state.lock().await;
+        let mut sessions: Vec<String> = state.chat_history.keys().cloned().collect();
+        sessions.sort();
+
+        Ok(Response::new(ListSessionsResponse { sessions }))
+    }
+
+    async fn health(
+        &self,
+        _request: Request<HealthRequest>,
+    ) -> Result<Response<HealthResponse>, Status> {
+        let response = if self.claude_client.has_api_key() {
+            HealthResponse {
+                healthy: true,
+                reason: String::new(),
+            }
+        } else {
+            HealthResponse {
+                healthy: false,
+                reason: "No Anthropic API key configured".to_string(),
+            }
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn append_history(
+        &self,
+        request: Request<AppendHistoryRequest>,
+    ) -> Result<Response<AppendHistoryResponse>, Status> {
+        let request = request.into_inner();
+        let mut turns = Vec::with_capacity(request.turns.len());
+        for turn in request.turns {
+            let role = match turn.role.as_str() {
+                "user" => Role::User,
+                "assistant" => Role::Assistant,
+                other => {
+                    return Err(Status::invalid_argument(format!(
+                        "Unknown role {other:?}; expected \"user\" or \"assistant\""
+                    )))
+                }
+            };
+            turns.push(HistoryEntry::new(role, turn.content));
+        }
+
+        let mut state = self.state.lock().await;
+        let history = state
+            .chat_history
+            .entry(session_key(&request.session_id))
+            .or_default();
+
+        // Claude requires turns to alternate, starting with the user, and the
+        // next spell adds a user turn, so the result must end with the assistant
+        let mut previous = history.last().map(|entry| entry.role);
+        for turn in &turns {
+            if previous == Some(turn.role) || (previous.is_none() && turn.role != Role::User) {
+                return Err(Status::invalid_argument(format!(
+                    "Turns must alternate between user and assistant, starting with user; got {} {}",
+                    turn.role.as_str(),
+                    previous.map_or("first".to_string(), |role| format!("after {}", role.as_str()))
+                )));
+            }
+            previous = Some(turn.role);
+        }
+        if previous == Some(Role::User) {
+            return Err(Status::invalid_argument(
+                "History must end with an assistant turn",
+            ));
+        }
+
+        info!("Appending {} turns to the history", turns.len());
+        history.extend(turns);
+        trim_history(history);
+
+        Ok(Response::new(AppendHistoryResponse {
+            history_len: history.len() as u32,
+        }))
+    }
+
+    async fn identity(
+        &self,
+        _request: Request<IdentityRequest>,
+    ) -> Result<Response<IdentityResponse>, Status> {
+        let name = self.state.lock().await.name.clone();
+        Ok(Response::new(IdentityResponse { name }))
+    }
+
+    async fn drain(
+        &self,
+        _request: Request<DrainRequest>,
+    ) -> Result<Response<DrainResponse>, Status> {
+        let mut state = self.state.lock().await;
+        if !state.draining {
+            info!("Draining: refusing new spells");
+            state.draining = true;
+        }
+        Ok(Response::new(DrainResponse {
+            in_flight: state.in_flight,
+        }))
+    }
+
+    async fn ping(&self, _request: Request<PingRequest>) -> Result<Response<PingResponse>, Status> {
+        Ok(Response::new(PingResponse {}))
+    }
+
+    async fn cancel_spell(
+        &self,
+        _request: Request<CancelSpellRequest>,
+    ) -> Result<Response<CancelSpellResponse>, Status> {
+        let spell = self.state.lock().await.current_spell.take();
+        if let Some(cancel) = &spell {
+            info!("Cancelling the current spell");
+            cancel.notify_one();
+        }
+
+        Ok(Response::new(CancelSpellResponse {
+            cancelled: spell.is_some(),
+        }))
+    }
+
+    async fn get_system_prompt(
+        &self,
+        _request: Request<GetSystemPromptRequest>,
+    ) -> Result<Response<GetSystemPromptResponse>, Status> {
+        let state = self.state.lock().await;
+
+        Ok(Response::new(GetSystemPromptResponse {
+            prompt: state.system_prompt.clone(),
+        }))
+    }
+
+    async fn set_system_prompt(
+        &self,
+        request: Request<SetSystemPromptRequest>,
+    ) -> Result<Response<SetSystemPromptResponse>, Status> {
+        let prompt = request.into_inner().prompt;
+        let mut state = self.state.lock().await;
+
+        // History is kept; the new prompt applies from the next spell
+        state.system_prompt = (!prompt.trim().is_empty()).then_some(prompt);
+        info!(
+            "System prompt {}",
+            if state.system_prompt.is_some() {
+                "replaced"
+            } else {
+                "cleared"
+            }
+        );
+
+        Ok(Response::new(SetSystemPromptResponse {}))
+    }
+
+    async fn get_last_incantation(
+        &self,
+        request: Request<LastIncantationRequest>,
+    ) -> Result<Response<LastIncantationResponse>, Status> {
+        let session = session_key(&request.into_inner().session_id);
+        let state = self.state.lock().await;
+
+        Ok(Response::new(LastIncantationResponse {
+            incantation: state.last_incantation.get(&session).cloned(),
+        }))
+    }
 }
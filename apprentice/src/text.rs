@@ -0,0 +1,13 @@
+//! Text helpers shared by the apprentice and, through `#[path]`, the sorcerer.
+//! They live here because the apprentice's container build only sees
+//! `apprentice/src`.
+
+/// Return the longest prefix of `s` that is at most `max` characters long.
+///
+/// Cuts only on `char` boundaries, so multibyte text never panics.
+pub fn truncate_on_char_boundary(s: &str, max: usize) -> &str {
+    match s.char_indices().nth(max) {
+        Some((cut, _)) => &s[..cut],
+        None => s,
+    }
+}
@@ -2,8 +2,26 @@ use std::env;
 
 pub struct Config {
     pub image_name: String,
+    pub runtime: String,
     pub starting_port: u16,
+    pub hash_ports: bool,
     pub container_ready_timeout: u64,
+    pub overview_lines: usize,
+    pub history_lines: usize,
+    pub connect_attempts: u32,
+    pub connect_retry_delay_ms: u64,
+    pub tls_cert: Option<String>,
+    pub tls_key: Option<String>,
+    pub tls_ca: Option<String>,
+    pub tls_domain: String,
+    pub auth_token: Option<String>,
+    pub auto_pull: bool,
+    pub require_pinned: bool,
+    pub network_mode: String,
+    pub restart_policy: String,
+    pub keepalive_interval_secs: u64,
+    pub keepalive_timeout_secs: u64,
+    pub connect_timeout_secs: u64,
 }
 
 impl Default for Config {
@@ -11,14 +29,93 @@ impl Default for Config {
         Self {
             image_name: env::var("SORCERER_IMAGE")
                 .unwrap_or_else(|_| "sorcerer-apprentice:latest".to_string()),
+            runtime: env::var("SORCERER_RUNTIME").unwrap_or_else(|_| "auto".to_string()),
             starting_port: env::var("SORCERER_STARTING_PORT")
                 .ok()
                 .and_then(|p| p.parse().ok())
                 .unwrap_or(50100),
+            hash_ports: env::var("SORCERER_PORT_STRATEGY").is_ok_and(|s| s == "hash"),
             container_ready_timeout: env::var("SORCERER_CONTAINER_TIMEOUT")
                 .ok()
                 .and_then(|t| t.parse().ok())
                 .unwrap_or(2),
+            overview_lines: env::var("SORCERER_OVERVIEW_LINES")
+                .ok()
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(4),
+            // Large default to get all history
+            history_lines: env::var("SORCERER_HISTORY_LINES")
+                .ok()
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(1000),
+            connect_attempts: env::var("SORCERER_CONNECT_ATTEMPTS")
+                .ok()
+                .and_then(|a| a.parse().ok())
+                .filter(|a| *a > 0)
+                .unwrap_or(4),
+            connect_retry_delay_ms: env::var("SORCERER_CONNECT_RETRY_DELAY_MS")
+                .ok()
+                .and_then(|d| d.parse().ok())
+                .unwrap_or(250),
+            tls_cert: env::var("SORCERER_TLS_CERT").ok(),
+            tls_key: env::var("SORCERER_TLS_KEY").ok(),
+            // A self-signed server certificate doubles as its own CA
+            tls_ca: env::var("SORCERER_TLS_CA")
+                .ok()
+                .or_else(|| env::var("SORCERER_TLS_CERT").ok()),
+            auto_pull: env::var("SORCERER_AUTO_PULL").is_ok_and(|v| !v.is_empty() && v != "0"),
+            require_pinned: env::var("SORCERER_REQUIRE_PINNED")
+                .is_ok_and(|v| !v.is_empty() && v != "0"),
+            auth_token: env::var("SORCERER_TOKEN").ok().filter(|t| !t.is_empty()),
+            tls_domain: env::var("SORCERER_TLS_DOMAIN").unwrap_or_else(|_| "localhost".to_string()),
+            restart_policy: env::var("SORCERER_RESTART_POLICY")
+                .unwrap_or_else(|_| "no".to_string()),
+            keepalive_interval_secs: env::var("SORCERER_KEEPALIVE_INTERVAL")
+                .ok()
+                .and_then(|i| i.parse().ok())
+                .unwrap_or(30),
+            keepalive_timeout_secs: env::var("SORCERER_KEEPALIVE_TIMEOUT")
+                .ok()
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(10),
+            connect_timeout_secs: env::var("SORCERER_CONNECT_TIMEOUT")
+                .ok()
+                .and_then(|t| t.parse().ok())
+                .filter(|t| *t > 0)
+                .unwrap_or(3),
+            // Docker Desktop on macOS/Windows does not support host networking
+            network_mode: env::var("SORCERER_NETWORK_MODE").unwrap_or_else(|_| {
+                if cfg!(target_os = "linux") {
+                    "host".to_string()
+                } else {
+                    "bridge".to_string()
+                }
+            }),
+        }
+    }
+}
+
+impl Config {
+    /// Apprentices serve TLS, and are dialled over https, only when both the
+    /// certificate and key are configured.
+    pub fn tls_enabled(&self) -> bool {
+        self.tls_files().is_some()
+    }
+
+    /// The certificate and key paths, when TLS is enabled.
+    pub fn tls_files(&self) -> Option<(&str, &str)> {
+        Some((self.tls_cert.as_deref()?, self.tls_key.as_deref()?))
+    }
+
+    /// Reject partial TLS settings, which would leave the sorcerer dialling
+    /// https while its apprentices serve plaintext, or the reverse.
+    pub fn check_tls(&self) -> anyhow::Result<()> {
+        if self.tls_cert.is_some() != self.tls_key.is_some() {
+            anyhow::bail!("SORCERER_TLS_CERT and SORCERER_TLS_KEY must be set together");
+        }
+        if !self.tls_enabled() && self.tls_ca.is_some() {
+            anyhow::bail!("SORCERER_TLS_CA requires SORCERER_TLS_CERT and SORCERER_TLS_KEY");
         }
+        Ok(())
     }
 }
@@ -0,0 +1,128 @@
+use crate::sorcerer::SummonOptions;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Where a spec's `prompt` file is mounted in the apprentice's container.
+const PROMPT_MOUNT: &str = "/etc/apprentice/prompt.md";
+/// Where a spec's `workspace` directory is mounted in the apprentice's container.
+const WORKSPACE_MOUNT: &str = "/workspace";
+
+/// A declarative description of a set of apprentices, loaded by `srcrr apply`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FleetSpec {
+    pub apprentices: Vec<ApprenticeSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApprenticeSpec {
+    pub name: String,
+    /// Claude model the apprentice uses by default
+    pub model: Option<String>,
+    /// Memory limit, e.g. "512m" or "2g"
+    pub memory: Option<String>,
+    /// Number of CPUs the container may use, e.g. 1.5
+    pub cpus: Option<f64>,
+    /// Container image to use instead of SORCERER_IMAGE
+    pub image: Option<String>,
+    /// System prompt file, loaded by the apprentice as its `SYSTEM_PROMPT_PATH`
+    pub prompt: Option<PathBuf>,
+    /// Host directory mounted into the container as the apprentice's workspace
+    pub workspace: Option<PathBuf>,
+}
+
+impl ApprenticeSpec {
+    /// The summon settings for this apprentice. Relative `prompt` and
+    /// `workspace` paths are resolved against `base_dir`, the spec's directory.
+    pub fn summon_options(&self, base_dir: &Path) -> Result<SummonOptions> {
+        let mut options = SummonOptions {
+            model: self.model.clone(),
+            memory: self.memory.as_deref().map(parse_memory).transpose()?,
+            nano_cpus: self.cpus.map(|c| (c * 1e9) as i64),
+            image: self.image.clone(),
+            ..Default::default()
+        };
+
+        let resolve = |path: &Path| {
+            base_dir
+                .join(path)
+                .canonicalize()
+                .with_context(|| format!("Cannot find {}", path.display()))
+        };
+        if let Some(prompt) = &self.prompt {
+            let prompt = resolve(prompt)?;
+            options
+                .mounts
+                .push(format!("{}:{PROMPT_MOUNT}:ro", prompt.display()));
+            options
+                .env
+                .push(format!("SYSTEM_PROMPT_PATH={PROMPT_MOUNT}"));
+        }
+        if let Some(workspace) = &self.workspace {
+            let workspace = resolve(workspace)?;
+            options
+                .mounts
+                .push(format!("{}:{WORKSPACE_MOUNT}", workspace.display()));
+            options.env.push(format!("WORKSPACE={WORKSPACE_MOUNT}"));
+        }
+
+        Ok(options)
+    }
+}
+
+impl FleetSpec {
+    /// Load a spec from a `.toml` file, or from YAML for any other extension.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fleet spec {}", path.display()))?;
+        let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+        Self::parse(&contents, is_toml)
+            .with_context(|| format!("Invalid fleet spec {}", path.display()))
+    }
+
+    pub fn parse(contents: &str, is_toml: bool) -> Result<Self> {
+        let spec: Self = if is_toml {
+            toml::from_str(contents)?
+        } else {
+            serde_yaml::from_str(contents)?
+        };
+
+        let mut seen = std::collections::HashSet::new();
+        for apprentice in &spec.apprentices {
+            if !seen.insert(apprentice.name.as_str()) {
+                return Err(anyhow!("Apprentice {} is declared twice", apprentice.name));
+            }
+            if let Some(cpus) = apprentice.cpus {
+                if !cpus.is_finite() || cpus <= 0.0 {
+                    return Err(anyhow!(
+                        "Apprentice {} has invalid cpus {}; it must be greater than 0",
+                        apprentice.name,
+                        cpus
+                    ));
+                }
+            }
+        }
+
+        Ok(spec)
+    }
+}
+
+/// Parse a memory size like "512m", "2g" or "1048576" into bytes.
+pub fn parse_memory(value: &str) -> Result<i64> {
+    let value = value.trim().to_ascii_lowercase();
+    let (number, multiplier) = match value.chars().last() {
+        Some('k') => (&value[..value.len() - 1], 1024),
+        Some('m') => (&value[..value.len() - 1], 1024 * 1024),
+        Some('g') => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value.as_str(), 1),
+    };
+
+    number
+        .parse::<i64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| anyhow!("Invalid memory size: {}", value))
+}
@@ -1,5 +1,9 @@
 pub mod config;
+pub mod fleet;
 pub mod sorcerer;
+#[path = "../apprentice/src/text.rs"]
+pub mod text;
+pub mod transcript;
 pub use sorcerer::*;
 
 // Re-export the protobuf types for testing
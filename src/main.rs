@@ -1,9 +1,19 @@
 mod config;
+mod fleet;
+mod serve;
 mod sorcerer;
+#[path = "../apprentice/src/text.rs"]
+mod text;
+mod transcript;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use tracing::error;
+use futures_util::StreamExt;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use tracing::{error, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Parser)]
@@ -11,16 +21,80 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 #[command(about = "🧙‍♂️ The Sorcerer - Command apprentices to do your bidding")]
 #[command(version = "0.1.0")]
 struct Cli {
+    /// Suppress decorative output, printing only results and errors
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Show timing, model and token details alongside results
+    #[arg(short, long, global = true, conflicts_with = "quiet")]
+    verbose: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Create and start a new apprentice container
+    /// Create and start new apprentice containers
     Summon {
-        /// Name of the apprentice to create
-        name: String,
+        /// Names of the apprentices to create
+        #[arg(required = true)]
+        names: Vec<String>,
+        /// Print a machine-readable JSON summary instead of status lines
+        #[arg(long)]
+        json: bool,
+        /// Extra environment variable for the apprentice container (repeatable)
+        #[arg(long = "env", value_name = "KEY=VALUE", value_parser = parse_env_var)]
+        env: Vec<String>,
+        /// Container image to use instead of SORCERER_IMAGE
+        #[arg(long, value_name = "NAME:TAG")]
+        image: Option<String>,
+        /// Poll each apprentice's health on a fixed interval until a deadline;
+        /// exits with status 3 if the deadline passes first
+        #[arg(long)]
+        wait_healthy: bool,
+        /// Milliseconds between readiness checks
+        #[arg(
+            long,
+            value_name = "MS",
+            default_value = "500",
+            requires = "wait_healthy"
+        )]
+        interval: u64,
+        /// Seconds to wait for each apprentice to become healthy
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value = "60",
+            requires = "wait_healthy"
+        )]
+        deadline: u64,
+        /// Maximum number of apprentices to summon at once
+        #[arg(long, value_name = "N", default_value = "4")]
+        concurrency: usize,
+        /// Print the containers, ports and images that would be used, without
+        /// creating anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Leave the container of an apprentice that fails to start, for
+        /// inspection; by default it is removed
+        #[arg(long)]
+        keep_on_failure: bool,
+        /// JSON transcript of a prior conversation to load into each
+        /// apprentice once it is up
+        #[arg(long, value_name = "PATH")]
+        seed_history: Option<std::path::PathBuf>,
+        /// File of extra instructions to add after the system prompt
+        #[arg(long, value_name = "PATH")]
+        prompt_append: Option<std::path::PathBuf>,
+        /// An extra instruction to add after the system prompt (repeatable)
+        #[arg(long, value_name = "TEXT")]
+        instruction: Vec<String>,
+    },
+    /// Summon the apprentices declared in a YAML or TOML fleet file
+    Apply {
+        /// Path to the fleet spec
+        file: std::path::PathBuf,
     },
     /// Send a message to an apprentice and get its response
     Tell {
@@ -28,97 +102,891 @@ enum Commands {
         name: String,
         /// The message to send
         message: String,
+        /// Use this model for the message instead of the apprentice's default
+        #[arg(long)]
+        model: Option<String>,
+        /// Conversation thread to continue (default: "default")
+        #[arg(long)]
+        session: Option<String>,
+        /// Include a file's contents as context ahead of the message (repeatable)
+        #[arg(long = "attach", value_name = "PATH")]
+        attachments: Vec<std::path::PathBuf>,
+        /// Resend the message whenever files under this path change, until Ctrl-C
+        #[arg(long, value_name = "DIR")]
+        watch: Option<std::path::PathBuf>,
+        /// Print just the reply, or a JSON object with the spell id, token usage and latency
+        #[arg(long, value_enum, default_value = "text")]
+        output: OutputFormat,
+        /// Shorthand for --output plain: only the reply goes to stdout
+        #[arg(long, conflicts_with = "output")]
+        plain: bool,
+        /// Send the message even if it is empty or only whitespace
+        #[arg(long)]
+        allow_empty: bool,
+        /// Times to resend if the connection to the apprentice drops; errors
+        /// from Claude are never retried
+        #[arg(long, value_name = "N", default_value = "1")]
+        retries: u32,
+    },
+    /// Send the same message to two apprentices and diff their replies
+    Diff {
+        /// First apprentice
+        first: String,
+        /// Second apprentice
+        second: String,
+        /// The message to send to both
+        message: String,
+        /// Conversation thread to use on both (default: "default")
+        #[arg(long)]
+        session: Option<String>,
     },
-    /// List all active apprentices
-    List,
-    /// Stop and remove an apprentice container
+    /// Re-send the last message an apprentice was told
+    Replay {
+        /// Name of the apprentice to retry
+        name: String,
+        /// Conversation thread to replay from (default: "default")
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// List all active apprentices (with --quiet, bare names for scripting)
+    #[command(alias = "ls")]
+    List {
+        /// Show state, port and container id columns
+        #[arg(short, long)]
+        long: bool,
+        /// Show when each apprentice last answered a message
+        #[arg(long, conflicts_with = "long")]
+        last: bool,
+    },
+    /// Stop and remove apprentice containers
     Kill {
-        /// Name of the apprentice to remove
+        /// Names of the apprentices to remove
+        #[arg(required = true)]
+        names: Vec<String>,
+        /// Print a machine-readable JSON summary instead of status lines
+        #[arg(long)]
+        json: bool,
+        /// Skip the graceful shutdown and force-remove the containers
+        #[arg(long)]
+        force: bool,
+        /// Maximum number of apprentices to kill at once
+        #[arg(long, value_name = "N", default_value = "4")]
+        concurrency: usize,
+        /// Print the containers that would be removed, without removing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Refuse new spells and let the one in flight finish before removing
+        #[arg(long, conflicts_with = "force")]
+        drain: bool,
+    },
+    /// Stop an apprentice accepting new spells, letting its current one finish
+    Drain {
+        /// Name of the apprentice to drain
         name: String,
     },
+    /// Remove apprentice containers that are stopped, dead or not answering
+    Reap {
+        /// Print the containers that would be removed, without removing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Print a machine-readable JSON summary instead of status lines
+        #[arg(long)]
+        json: bool,
+    },
     /// Show detailed status information for all apprentices
     Overview {
         /// Number of recent chat history lines to show
-        #[arg(short, long, default_value = "4")]
-        lines: usize,
+        /// (default: SORCERER_OVERVIEW_LINES, or 4)
+        #[arg(short, long)]
+        lines: Option<usize>,
+    },
+    /// Continuously redraw the status of all apprentices
+    Top {
+        /// Seconds between refreshes
+        #[arg(short, long, default_value = "2")]
+        interval: u64,
+    },
+    /// Print new chat history lines from several apprentices as they arrive
+    Follow {
+        /// Apprentices to follow
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        names: Vec<String>,
+        /// Follow every apprentice, including ones summoned later
+        #[arg(long)]
+        all: bool,
+        /// Conversation thread to follow (default: "default")
+        #[arg(long)]
+        session: Option<String>,
+        /// Seconds between polls
+        #[arg(short, long, default_value = "2")]
+        interval: u64,
     },
     /// View and scroll through chat history with an apprentice
     History {
         /// Name of the apprentice to view history for
         name: String,
-        /// Number of history lines to show (default: all)
+        /// Number of history lines to show (default: SORCERER_HISTORY_LINES, or all)
         #[arg(short, long)]
         lines: Option<usize>,
+        /// Conversation thread to view (default: "default")
+        #[arg(long)]
+        session: Option<String>,
+        /// Print each entry with a template using {role}, {text} and {time},
+        /// or a preset: plain, markdown, slack
+        #[arg(long, value_name = "TEMPLATE")]
+        format: Option<String>,
+        /// Only show lines matching this regex (case-insensitive by default)
+        #[arg(long, value_name = "REGEX")]
+        grep: Option<String>,
+        /// Make --grep case-sensitive
+        #[arg(long, requires = "grep")]
+        case_sensitive: bool,
     },
+    /// Show how a running apprentice is configured
+    Inspect {
+        /// Name of the apprentice to inspect
+        name: String,
+        /// Print the container runtime's inspect JSON instead of asking the apprentice
+        #[arg(long)]
+        raw: bool,
+    },
+    /// Show or replace an apprentice's system prompt
+    Prompt {
+        /// Name of the apprentice
+        name: String,
+        /// Replace the system prompt with the contents of this file
+        /// (an empty file clears it)
+        #[arg(long, value_name = "PATH")]
+        set: Option<std::path::PathBuf>,
+    },
+    /// Abort the spell an apprentice is currently casting
+    Cancel {
+        /// Name of the apprentice to interrupt
+        name: String,
+    },
+    /// Measure gRPC round-trip time to an apprentice, without calling Claude
+    Ping {
+        /// Name of the apprentice to ping
+        name: String,
+        /// Number of pings to send; more than one also prints min/avg/max
+        #[arg(short, long, value_name = "N", default_value = "1")]
+        count: usize,
+    },
+    /// Print an apprentice's raw container logs
+    Logs {
+        /// Name of the apprentice whose logs to show
+        name: String,
+        /// Number of lines to show from the end of the logs
+        #[arg(short = 'n', long, default_value = "100")]
+        tail: usize,
+        /// Only show logs after this RFC 3339 timestamp
+        #[arg(long)]
+        since: Option<String>,
+        /// Keep streaming new output until Ctrl-C
+        #[arg(short, long)]
+        follow: bool,
+    },
+    /// Run a long-lived HTTP server exposing the commands as a JSON REST API
+    Serve {
+        /// Port to listen on (bound to 127.0.0.1)
+        #[arg(short, long, default_value = "8080")]
+        port: u16,
+    },
+    /// List the conversation threads of an apprentice
+    Sessions {
+        /// Name of the apprentice to list sessions for
+        name: String,
+    },
+}
+
+/// How `tell` prints a reply.
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    /// Only the reply on stdout, for piping; failures go to stderr
+    Plain,
+}
+
+/// How much `cast_and_print` says around a reply, from `--quiet`/`--verbose`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+impl Verbosity {
+    fn new(quiet: bool, verbose: bool) -> Self {
+        if quiet {
+            Self::Quiet
+        } else if verbose {
+            Self::Verbose
+        } else {
+            Self::Normal
+        }
+    }
+}
+
+/// Print decorative status output, which `--quiet` suppresses.
+macro_rules! status {
+    ($quiet:expr, $($arg:tt)*) => {
+        if !$quiet {
+            println!($($arg)*);
+        }
+    };
+}
+
+#[derive(Serialize)]
+struct BatchFailure {
+    name: String,
+    error: String,
+}
+
+#[derive(Serialize)]
+struct BatchSummary {
+    requested: usize,
+    succeeded: usize,
+    failed: Vec<BatchFailure>,
+    /// Apprentices that were created but failed a follow-up health check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    not_responding: Option<Vec<BatchFailure>>,
+}
+
+impl BatchSummary {
+    fn new(requested: usize) -> Self {
+        Self {
+            requested,
+            succeeded: 0,
+            failed: Vec::new(),
+            not_responding: None,
+        }
+    }
+
+    fn report(&self, action: &str, json: bool, quiet: bool) -> Result<()> {
+        if json {
+            println!("{}", serde_json::to_string(self)?);
+        } else if self.requested > 1 && !quiet {
+            println!(
+                "📊 Summary: {}/{} apprentices {action}",
+                self.succeeded, self.requested
+            );
+        }
+        Ok(())
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG").unwrap_or_else(|_| "sorcerer=info".into()),
-        ))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let registry = tracing_subscriber::registry().with(tracing_subscriber::EnvFilter::new(
+        std::env::var("RUST_LOG").unwrap_or_else(|_| "sorcerer=info".into()),
+    ));
+    if std::env::var("LOG_FORMAT").is_ok_and(|f| f == "json") {
+        registry
+            .with(tracing_subscriber::fmt::layer().json())
+            .init();
+    } else {
+        registry.with(tracing_subscriber::fmt::layer()).init();
+    }
 
     let cli = Cli::parse();
-    let mut sorcerer = sorcerer::Sorcerer::new().await?;
+    let quiet = cli.quiet;
+    let verbose = cli.verbose;
+    let sorcerer = sorcerer::Sorcerer::new().await?;
 
     match cli.command {
-        Commands::Summon { name } => {
-            println!("🌟 Summoning apprentice {name}...");
-            match sorcerer.summon_apprentice(&name).await {
-                Ok(_) => {
-                    println!("✨ Apprentice {name} has answered your call!");
+        Commands::Summon {
+            names,
+            json,
+            env,
+            image,
+            wait_healthy,
+            interval,
+            deadline,
+            concurrency,
+            dry_run,
+            keep_on_failure,
+            seed_history,
+            prompt_append,
+            instruction,
+        } => {
+            let mut extra_instructions = Vec::new();
+            if let Some(path) = &prompt_append {
+                let text = std::fs::read_to_string(path).with_context(|| {
+                    format!("Failed to read prompt addition {}", path.display())
+                })?;
+                extra_instructions.push(text.trim().to_string());
+            }
+            extra_instructions.extend(instruction);
+            extra_instructions.retain(|text| !text.trim().is_empty());
+            let transcript = seed_history
+                .as_deref()
+                .map(transcript::Transcript::from_file)
+                .transpose()?;
+            let options = sorcerer::SummonOptions {
+                env,
+                keep_on_failure,
+                prompt_append: (!extra_instructions.is_empty())
+                    .then(|| extra_instructions.join("\n\n")),
+                image,
+                health_wait: wait_healthy.then(|| sorcerer::HealthWait {
+                    interval: std::time::Duration::from_millis(interval.max(1)),
+                    deadline: std::time::Duration::from_secs(deadline),
+                }),
+                ..Default::default()
+            };
+            if dry_run {
+                let plans = sorcerer.plan_summon(&names, &options).await?;
+                if json {
+                    let plans: Vec<_> = plans
+                        .iter()
+                        .map(|p| {
+                            serde_json::json!({
+                                "name": p.name,
+                                "container": p.container_name,
+                                "port": p.port,
+                                "image": p.image,
+                            })
+                        })
+                        .collect();
+                    println!(
+                        "{}",
+                        serde_json::json!({ "dry_run": true, "summon": plans })
+                    );
+                } else {
+                    println!("🔍 Dry run: would summon {} apprentice(s)", plans.len());
+                    for p in plans {
+                        println!(
+                            "  {} -> container {} on port {} from {}",
+                            p.name, p.container_name, p.port, p.image
+                        );
+                    }
                 }
-                Err(e) => {
-                    error!("Failed to summon apprentice: {}", e);
-                    println!("💀 The summoning failed");
+                return Ok(());
+            }
+            let total = names.len();
+            let mut summary = BatchSummary::new(total);
+            let mut summoned = Vec::new();
+            let mut timed_out = false;
+            let mut results = futures_util::stream::iter(names.into_iter().map(|name| {
+                status!(quiet || json, "🌟 Summoning apprentice {name}...");
+                let options = &options;
+                let sorcerer = &sorcerer;
+                let transcript = transcript.as_ref();
+                async move {
+                    let started = std::time::Instant::now();
+                    let mut result = sorcerer.summon_apprentice(&name, options).await;
+                    if let (Ok(_), Some(transcript)) = (&result, transcript) {
+                        if let Err(e) = sorcerer.append_history(&name, transcript).await {
+                            result = Err(anyhow::anyhow!(
+                                "Summoned, but loading its seed history failed: {e}"
+                            ));
+                        }
+                    }
+                    (name, result, started.elapsed())
+                }
+            }))
+            .buffer_unordered(concurrency.max(1));
+            let mut done = 0;
+            while let Some((name, result, elapsed)) = results.next().await {
+                done += 1;
+                let took = timing_note(verbose, elapsed);
+                match result {
+                    Ok(_) => {
+                        summary.succeeded += 1;
+                        status!(
+                            quiet || json,
+                            "✨ Apprentice {name} has answered your call! ({done}/{total}{took})"
+                        );
+                        summoned.push(name);
+                    }
+                    Err(e) => {
+                        error!("Failed to summon apprentice: {}", e);
+                        if quiet && !json {
+                            eprintln!("Failed to summon {name}: {e}");
+                        }
+                        status!(
+                            quiet || json,
+                            "💀 The summoning of {name} failed: {e} ({done}/{total}{took})"
+                        );
+                        timed_out |= e
+                            .chain()
+                            .any(|cause| cause.is::<sorcerer::HealthDeadlineElapsed>());
+                        summary.failed.push(BatchFailure {
+                            name,
+                            error: e.to_string(),
+                        });
+                    }
+                }
+            }
+
+            // An apprentice can pass its first health check and still crash
+            // soon after, so check the whole batch once more at the end
+            let mut not_responding = Vec::new();
+            if summoned.len() > 1 {
+                status!(
+                    quiet || json,
+                    "🩺 Checking that the new apprentices answer..."
+                );
+                for name in summoned {
+                    match sorcerer.check_health(&name).await {
+                        Ok(()) => status!(quiet || json, "  💚 {name} is answering"),
+                        Err(e) => {
+                            status!(
+                                quiet || json,
+                                "  💔 {name} was created but is not responding: {e}"
+                            );
+                            not_responding.push(BatchFailure {
+                                name,
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+            let unhealthy = !not_responding.is_empty();
+            summary.not_responding = Some(not_responding);
+
+            summary.report("summoned", json, quiet)?;
+            if timed_out {
+                std::process::exit(3);
+            }
+            if !summary.failed.is_empty() || unhealthy {
+                std::process::exit(1);
+            }
+        }
+        Commands::Apply { file } => {
+            let spec = fleet::FleetSpec::from_file(&file)?;
+            let base_dir = file.parent().unwrap_or(std::path::Path::new("."));
+            let existing = sorcerer.list_apprentices().await?;
+            status!(quiet, "📐 Applying fleet spec {}...", file.display());
+
+            let mut summary = BatchSummary::new(0);
+            for apprentice in &spec.apprentices {
+                let name = &apprentice.name;
+                if existing.contains(name) {
+                    status!(quiet, "  = {name} (already running, skipped)");
+                    continue;
+                }
+
+                summary.requested += 1;
+                let result = match apprentice.summon_options(base_dir) {
+                    Ok(options) => sorcerer.summon_apprentice(name, &options).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(_) => {
+                        summary.succeeded += 1;
+                        status!(quiet, "  + {name}");
+                    }
+                    Err(e) => {
+                        error!("Failed to summon apprentice: {}", e);
+                        if quiet {
+                            eprintln!("Failed to summon {name}: {e}");
+                        }
+                        status!(quiet, "  ! {name} (summoning failed)");
+                        summary.failed.push(BatchFailure {
+                            name: name.clone(),
+                            error: e.to_string(),
+                        });
+                    }
                 }
             }
+            for name in &existing {
+                if !spec.apprentices.iter().any(|a| &a.name == name) {
+                    status!(quiet, "  ? {name} (running but not in spec, left alone)");
+                }
+            }
+
+            summary.report("summoned", false, quiet)?;
+            if !summary.failed.is_empty() {
+                std::process::exit(1);
+            }
         }
-        Commands::Tell { name, message } => {
-            println!("📜 Sending message to apprentice {name}...");
-            match sorcerer.cast_spell(&name, &message).await {
-                Ok(response) => {
-                    println!("🔮 The apprentice responds:");
-                    println!("{response}");
+        Commands::Tell {
+            name,
+            message,
+            model,
+            session,
+            attachments,
+            watch,
+            output,
+            plain,
+            retries,
+            allow_empty,
+        } => {
+            let output = if plain { OutputFormat::Plain } else { output };
+            // JSON output stands alone, so leave out the decorations
+            let quiet = quiet || output == OutputFormat::Json;
+            // Plain output keeps the spinner on stderr but nothing else
+            let decorated = !quiet && output == OutputFormat::Text;
+            let options = sorcerer::SpellOptions {
+                model,
+                session,
+                allow_empty,
+            };
+            let full_message = with_attachments(&message, &attachments)?;
+            status!(!decorated, "📜 Sending message to apprentice {name}...");
+            let succeeded = cast_and_print(
+                &sorcerer,
+                &name,
+                &full_message,
+                &options,
+                output,
+                retries,
+                Verbosity::new(quiet, verbose),
+            )
+            .await;
+            if let Some(dir) = watch {
+                let request = WatchedMessage {
+                    name: &name,
+                    message: &message,
+                    attachments: &attachments,
+                    options: &options,
+                    output,
+                    retries,
+                    verbose,
+                };
+                tell_on_change(&sorcerer, &request, &dir, !decorated).await?;
+            } else if !succeeded && output == OutputFormat::Plain {
+                std::process::exit(1);
+            }
+        }
+        Commands::Diff {
+            first,
+            second,
+            message,
+            session,
+        } => {
+            let options = sorcerer::SpellOptions {
+                session,
+                ..Default::default()
+            };
+            status!(
+                quiet,
+                "⚖️  Sending the same message to {first} and {second}..."
+            );
+            let spinner = if quiet {
+                None
+            } else {
+                start_spinner("The apprentices are pondering...")
+            };
+            let (first_reply, second_reply) = tokio::join!(
+                sorcerer.cast_spell(&first, &message, &options),
+                sorcerer.cast_spell(&second, &message, &options),
+            );
+            if let Some(spinner) = spinner {
+                spinner.finish_and_clear();
+            }
+
+            match (first_reply, second_reply) {
+                (Ok(first_reply), Ok(second_reply)) => {
+                    if first_reply == second_reply {
+                        status!(quiet, "🪞 Both apprentices gave the same answer:");
+                        println!("{first_reply}");
+                    } else {
+                        let diff = similar::TextDiff::from_lines(&first_reply, &second_reply);
+                        print!("{}", diff.unified_diff().header(&first, &second));
+                    }
+                }
+                (first_reply, second_reply) => {
+                    for (name, reply) in [(&first, first_reply), (&second, second_reply)] {
+                        if let Err(e) = reply {
+                            error!("Message sending failed: {}", e);
+                            if quiet {
+                                eprintln!("Message to {name} failed: {e}");
+                            }
+                            status!(quiet, "💥 The message to {name} failed: {e}");
+                        }
+                    }
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Replay { name, session } => {
+            match sorcerer
+                .get_last_incantation(&name, session.as_deref())
+                .await
+            {
+                Ok(message) => {
+                    // Replay exactly what was sent, even if it was blank
+                    let options = sorcerer::SpellOptions {
+                        session,
+                        allow_empty: true,
+                        ..Default::default()
+                    };
+                    status!(quiet, "🔁 Replaying last message to apprentice {name}...");
+                    cast_and_print(
+                        &sorcerer,
+                        &name,
+                        &message,
+                        &options,
+                        OutputFormat::Text,
+                        0,
+                        Verbosity::new(quiet, verbose),
+                    )
+                    .await;
                 }
                 Err(e) => {
-                    error!("Message sending failed: {}", e);
-                    println!("💥 The message failed");
+                    error!("Failed to replay message: {}", e);
+                    if quiet {
+                        eprintln!("Failed to replay message to {name}: {e}");
+                    }
+                    status!(quiet, "💥 Nothing to replay: {e}");
                 }
             }
         }
-        Commands::List => {
-            println!("📋 Listing apprentices...");
-            println!();
+        Commands::List { long: true, .. } => {
+            let apprentices = sorcerer.list_apprentices_detailed().await?;
+            if apprentices.is_empty() {
+                status!(quiet, "The realm is empty - no apprentices found.");
+            } else {
+                let name_width = apprentices
+                    .iter()
+                    .map(|a| a.name.len())
+                    .max()
+                    .unwrap_or(0)
+                    .max("NAME".len());
+                status!(
+                    quiet,
+                    "{:<name_width$}  {:<12}  {:<5}  {:<12}  {:<19}  IMAGE",
+                    "NAME",
+                    "STATE",
+                    "PORT",
+                    "CONTAINER",
+                    "DIGEST"
+                );
+                for apprentice in apprentices {
+                    // "sha256:" plus the first 12 hex digits, like the container ID
+                    let digest = apprentice.image_digest.as_deref().unwrap_or("-");
+                    println!(
+                        "{:<name_width$}  {:<12}  {:<5}  {:<12}  {:<19}  {}",
+                        apprentice.name,
+                        apprentice.state,
+                        apprentice.port,
+                        apprentice
+                            .container_id
+                            .get(..12)
+                            .unwrap_or(&apprentice.container_id),
+                        digest.get(..19).unwrap_or(digest),
+                        apprentice.image
+                    );
+                }
+            }
+        }
+        Commands::List { long: false, last } => {
+            status!(quiet, "📋 Listing apprentices...");
+            status!(quiet, "");
             let apprentices = sorcerer.list_apprentices().await?;
+            let statuses = if last {
+                Some(sorcerer.get_all_status().await?)
+            } else {
+                None
+            };
+            let name_width = apprentices.iter().map(|a| a.len()).max().unwrap_or(0);
             if apprentices.is_empty() {
-                println!("The realm is empty - no apprentices found.");
+                status!(quiet, "The realm is empty - no apprentices found.");
             } else {
                 for apprentice in apprentices {
-                    println!("🧙 {apprentice}");
+                    let mut line = apprentice.clone();
+                    if let Some(statuses) = &statuses {
+                        let last_spell = match statuses.get(&apprentice) {
+                            Some(status) if status.last_spell_time.is_empty() => {
+                                "never".to_string()
+                            }
+                            Some(status) => format_spell_time(&status.last_spell_time),
+                            None => "unreachable".to_string(),
+                        };
+                        line = format!("{apprentice:<name_width$}  {last_spell}");
+                    }
+                    if quiet {
+                        println!("{line}");
+                    } else {
+                        println!("🧙 {line}");
+                    }
                 }
             }
         }
-        Commands::Kill { name } => {
-            println!("💀 Killing apprentice {name}...");
-            match sorcerer.kill_apprentice(&name).await {
-                Ok(_) => {
-                    println!("⚰️  Apprentice {name} has been killed!");
+        Commands::Kill {
+            names,
+            json,
+            force,
+            concurrency,
+            dry_run,
+            drain,
+        } => {
+            if dry_run {
+                let plans = sorcerer.plan_kill(&names).await?;
+                let mode = if force {
+                    "force-remove"
+                } else {
+                    "stop and remove"
+                };
+                if json {
+                    let plans: Vec<_> = plans
+                        .iter()
+                        .map(|p| {
+                            serde_json::json!({
+                                "name": p.name,
+                                "container_id": p.container_id,
+                                "image": p.image,
+                                "state": p.state,
+                            })
+                        })
+                        .collect();
+                    println!(
+                        "{}",
+                        serde_json::json!({ "dry_run": true, "force": force, "kill": plans })
+                    );
+                } else {
+                    println!("🔍 Dry run: would {mode} {} apprentice(s)", plans.len());
+                    for p in plans {
+                        println!(
+                            "  {} -> container {} ({}, {})",
+                            p.name,
+                            p.container_id.get(..12).unwrap_or(&p.container_id),
+                            p.image,
+                            p.state
+                        );
+                    }
                 }
-                Err(e) => {
-                    error!("Failed to kill apprentice: {}", e);
-                    println!("⚠️  Kill failed");
+                return Ok(());
+            }
+            let total = names.len();
+            let mut summary = BatchSummary::new(total);
+            let mut results = futures_util::stream::iter(names.into_iter().map(|name| {
+                status!(quiet || json, "💀 Killing apprentice {name}...");
+                let sorcerer = &sorcerer;
+                async move {
+                    let started = std::time::Instant::now();
+                    let result = async {
+                        if drain {
+                            sorcerer.drain_and_wait(&name).await?;
+                        }
+                        sorcerer.kill_apprentice(&name, force).await
+                    }
+                    .await;
+                    (name, result, started.elapsed())
+                }
+            }))
+            .buffer_unordered(concurrency.max(1));
+            let mut done = 0;
+            while let Some((name, result, elapsed)) = results.next().await {
+                done += 1;
+                let took = timing_note(verbose, elapsed);
+                match result {
+                    Ok(_) => {
+                        summary.succeeded += 1;
+                        status!(
+                            quiet || json,
+                            "⚰️  Apprentice {name} has been killed! ({done}/{total}{took})"
+                        );
+                    }
+                    Err(e) => {
+                        error!("Failed to kill apprentice: {}", e);
+                        if quiet && !json {
+                            eprintln!("Failed to kill {name}: {e}");
+                        }
+                        status!(
+                            quiet || json,
+                            "⚠️  Kill of {name} failed ({done}/{total}{took})"
+                        );
+                        summary.failed.push(BatchFailure {
+                            name,
+                            error: e.to_string(),
+                        });
+                    }
+                }
+            }
+            summary.report("killed", json, quiet)?;
+            if !summary.failed.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Commands::Drain { name } => match sorcerer.drain(&name).await {
+            Ok(0) => status!(quiet, "🚰 Apprentice {name} is drained and idle"),
+            Ok(in_flight) => status!(
+                quiet,
+                "🚰 Apprentice {name} is draining; it will finish its {in_flight} spell(s) in flight"
+            ),
+            Err(e) => {
+                error!("Failed to drain apprentice: {}", e);
+                if quiet {
+                    eprintln!("Failed to drain {name}: {e}");
+                }
+                status!(quiet, "💥 Failed to drain {name}");
+                std::process::exit(1);
+            }
+        },
+        Commands::Reap { dry_run, json } => {
+            let candidates = sorcerer.find_reapable().await?;
+            if candidates.is_empty() {
+                status!(
+                    quiet || json,
+                    "✨ Nothing to reap - every apprentice is answering."
+                );
+            }
+            if dry_run && json {
+                let plans: Vec<_> = candidates
+                    .iter()
+                    .map(|c| {
+                        serde_json::json!({
+                            "name": c.name,
+                            "container_id": c.container_id,
+                            "reason": c.reason,
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::json!({ "dry_run": true, "reap": plans }));
+                return Ok(());
+            }
+            if dry_run {
+                for candidate in &candidates {
+                    println!(
+                        "🔍 Would reap {} ({}, container {})",
+                        candidate.name,
+                        candidate.reason,
+                        candidate
+                            .container_id
+                            .get(..12)
+                            .unwrap_or(&candidate.container_id)
+                    );
+                }
+                return Ok(());
+            }
+
+            let mut summary = BatchSummary::new(candidates.len());
+            for candidate in candidates {
+                let name = &candidate.name;
+                match sorcerer.reap(&candidate).await {
+                    Ok(()) => {
+                        summary.succeeded += 1;
+                        status!(quiet || json, "🪦 Reaped {name} ({})", candidate.reason);
+                    }
+                    Err(e) => {
+                        error!("Failed to reap apprentice: {}", e);
+                        if quiet && !json {
+                            eprintln!("Failed to reap {name}: {e}");
+                        }
+                        status!(quiet || json, "⚠️  Reaping {name} failed: {e}");
+                        summary.failed.push(BatchFailure {
+                            name: candidate.name.clone(),
+                            error: e.to_string(),
+                        });
+                    }
                 }
             }
+            summary.report("reaped", json, quiet)?;
+            if !summary.failed.is_empty() {
+                std::process::exit(1);
+            }
         }
         Commands::Overview { lines } => {
-            println!("📊 Overview of apprentices...");
+            let lines = lines.unwrap_or_else(|| config::Config::default().overview_lines);
+            status!(quiet, "📊 Overview of apprentices...");
             let statuses = sorcerer.get_all_status().await?;
             if statuses.is_empty() {
-                println!("No apprentices found.");
+                status!(quiet, "No apprentices found.");
             } else {
                 let mut first = true;
                 for (name, status) in statuses {
@@ -127,78 +995,303 @@ async fn main() -> Result<()> {
                     }
                     first = false;
 
-                    // Calculate box width based on apprentice name length
-                    let min_width = 45;
-                    let name_header = format!(" Apprentice: {name} ");
-                    let box_width = min_width.max(name_header.len() + 2);
-
-                    // Draw apprentice info box
-                    println!("┌─{}─┐", name_header.pad_to_width(box_width - 4, '─'));
-                    println!(
-                        "│ State: {:<width$} │",
-                        status.state,
-                        width = box_width - 11
-                    );
-                    if !status.last_spell_time.is_empty() {
-                        // Parse and format timestamp to be shorter
-                        let short_time = if let Ok(dt) =
-                            chrono::DateTime::parse_from_rfc3339(&status.last_spell_time)
-                        {
-                            dt.format("%Y-%m-%d %H:%M:%S").to_string()
-                        } else {
-                            status.last_spell_time.clone()
-                        };
-                        let last_msg = format!("Last Message: {short_time}");
-                        println!("│ {:<width$} │", last_msg, width = box_width - 4);
-                    }
-                    println!("└{}┘", "─".repeat(box_width - 2));
+                    print_status_box(&name, &status);
 
                     // Show chat history without boxes
-                    match sorcerer.get_chat_history(&name, lines).await {
+                    match sorcerer.get_chat_history(&name, lines, None).await {
                         Ok(history) => {
-                            if !history.is_empty() {
-                                println!("\nRecent Chat History:");
-                                for line in history {
-                                    print_wrapped_chat_line(&line);
-                                }
+                            println!("\nRecent Chat History:");
+                            if history.is_empty() {
+                                println!("  (no messages yet)");
+                            }
+                            for line in history {
+                                print_wrapped_chat_line(&line);
                             }
                         }
                         Err(e) => {
-                            println!("\nCould not retrieve chat history: {e}");
+                            if quiet {
+                                eprintln!("Could not retrieve chat history for {name}: {e}");
+                            } else {
+                                println!("\nCould not retrieve chat history: {e}");
+                            }
                         }
                     }
                 }
             }
         }
-        Commands::History { name, lines } => {
-            println!("📜 Viewing chat history for apprentice {name}...");
+        Commands::History {
+            name,
+            lines,
+            session,
+            format,
+            grep,
+            case_sensitive,
+        } => {
+            let pattern = grep
+                .map(|grep| {
+                    regex::RegexBuilder::new(&grep)
+                        .case_insensitive(!case_sensitive)
+                        .build()
+                        .map_err(|e| anyhow::anyhow!("Invalid --grep pattern: {e}"))
+                })
+                .transpose()?;
+            status!(quiet, "📜 Viewing chat history for apprentice {name}...");
 
             // Get all history or specified number of lines
-            let history_lines = lines.unwrap_or(1000); // Large default to get all history
-            match sorcerer.get_chat_history(&name, history_lines).await {
-                Ok(history) => {
+            let history_lines = lines.unwrap_or_else(|| config::Config::default().history_lines);
+            let history = match &format {
+                Some(format) => sorcerer
+                    .get_chat_entries(&name, history_lines, session.as_deref())
+                    .await
+                    .map(|entries| {
+                        entries
+                            .iter()
+                            .map(|entry| format_history_entry(format, &name, entry))
+                            .collect::<Vec<_>>()
+                    }),
+                None => {
+                    sorcerer
+                        .get_chat_history(&name, history_lines, session.as_deref())
+                        .await
+                }
+            };
+            match history {
+                Ok(mut history) => {
                     if history.is_empty() {
-                        println!("No chat history found for apprentice {name}.");
+                        status!(quiet, "No chat history found for apprentice {name}.");
                         return Ok(());
                     }
 
+                    if let Some(pattern) = &pattern {
+                        history.retain(|line| pattern.is_match(line));
+                        if history.is_empty() {
+                            status!(quiet, "No history lines match {}.", pattern.as_str());
+                            return Ok(());
+                        }
+                        if std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+                        {
+                            for line in &mut history {
+                                *line = pattern
+                                    .replace_all(line, "\x1b[1;33m$0\x1b[0m")
+                                    .into_owned();
+                            }
+                        }
+                    }
+
                     // If we have many lines and no specific line count was requested, use pager
-                    if lines.is_none() && history.len() > 20 {
+                    if format.is_some() {
+                        for line in &history {
+                            println!("{line}");
+                        }
+                    } else if lines.is_none() && history.len() > 20 {
                         show_history_with_pager(&history)?;
                     } else {
                         // Show history directly with proper formatting
-                        println!();
+                        status!(quiet, "");
                         for line in &history {
                             print_wrapped_chat_line(line);
                         }
                         if history.len() >= history_lines && lines.is_none() {
-                            println!("\n(Showing last {history_lines} lines)");
+                            status!(quiet, "\n(Showing last {history_lines} lines)");
                         }
                     }
                 }
                 Err(e) => {
                     error!("Failed to get chat history: {}", e);
-                    println!("💥 Failed to retrieve chat history for {name}");
+                    if quiet {
+                        eprintln!("Failed to retrieve chat history for {name}: {e}");
+                    }
+                    status!(quiet, "💥 Failed to retrieve chat history for {name}");
+                }
+            }
+        }
+        Commands::Top { interval } => {
+            run_top(&sorcerer, interval.max(1)).await?;
+        }
+        Commands::Follow {
+            names,
+            all: _,
+            session,
+            interval,
+        } => {
+            status!(quiet, "👂 Following chat histories (Ctrl-C to stop)...");
+            run_follow(&sorcerer, &names, session.as_deref(), interval.max(1)).await?;
+        }
+        Commands::Inspect { name, raw: true } => match sorcerer.inspect_container(&name).await {
+            Ok(info) => println!("{}", serde_json::to_string_pretty(&info)?),
+            Err(e) => {
+                error!("Failed to inspect container: {}", e);
+                if quiet {
+                    eprintln!("Failed to inspect {name}: {e}");
+                }
+                status!(quiet, "💥 Failed to inspect the container of {name}: {e}");
+            }
+        },
+        Commands::Inspect { name, raw: false } => {
+            status!(quiet, "🔍 Inspecting apprentice {name}...");
+            match sorcerer.get_config(&name).await {
+                Ok(config) => {
+                    if let Ok(image) = sorcerer.get_image(&name).await {
+                        println!("Image:         {image}");
+                    }
+                    println!("Model:         {}", config.model);
+                    println!("Max tokens:    {}", config.max_tokens);
+                    println!(
+                        "System prompt: {}",
+                        if config.system_prompt_present {
+                            "present"
+                        } else {
+                            "none"
+                        }
+                    );
+                    println!("History turns: {}", config.history_len);
+                }
+                Err(e) => {
+                    error!("Failed to inspect apprentice: {}", e);
+                    if quiet {
+                        eprintln!("Failed to inspect {name}: {e}");
+                    }
+                    status!(quiet, "💥 Failed to inspect apprentice {name}");
+                }
+            }
+        }
+        Commands::Prompt {
+            name,
+            set: Some(path),
+        } => {
+            let prompt = std::fs::read_to_string(&path).map_err(|e| {
+                anyhow::anyhow!("Failed to read prompt file {}: {}", path.display(), e)
+            })?;
+            match sorcerer.set_system_prompt(&name, &prompt).await {
+                Ok(()) => status!(
+                    quiet,
+                    "📝 New system prompt set for {name}; it applies from the next message"
+                ),
+                Err(e) => {
+                    error!("Failed to set system prompt: {}", e);
+                    if quiet {
+                        eprintln!("Failed to set system prompt for {name}: {e}");
+                    }
+                    status!(quiet, "💥 Failed to set system prompt for {name}");
+                }
+            }
+        }
+        Commands::Prompt { name, set: None } => match sorcerer.get_system_prompt(&name).await {
+            Ok(Some(prompt)) => println!("{prompt}"),
+            Ok(None) => status!(quiet, "Apprentice {name} has no system prompt."),
+            Err(e) => {
+                error!("Failed to get system prompt: {}", e);
+                if quiet {
+                    eprintln!("Failed to get system prompt for {name}: {e}");
+                }
+                status!(quiet, "💥 Failed to get system prompt for {name}");
+            }
+        },
+        Commands::Cancel { name } => match sorcerer.cancel_spell(&name).await {
+            Ok(true) => status!(quiet, "✋ Apprentice {name} has abandoned its spell"),
+            Ok(false) => status!(quiet, "😴 Apprentice {name} was not casting anything"),
+            Err(e) => {
+                error!("Failed to cancel spell: {}", e);
+                if quiet {
+                    eprintln!("Failed to cancel spell for {name}: {e}");
+                }
+                status!(quiet, "💥 Failed to cancel spell for {name}");
+            }
+        },
+        Commands::Ping { name, count } => {
+            let mut times = Vec::new();
+            for seq in 1..=count.max(1) {
+                match sorcerer.ping(&name).await {
+                    Ok(elapsed) => {
+                        let ms = elapsed.as_secs_f64() * 1000.0;
+                        if quiet {
+                            println!("{ms:.2}");
+                        } else {
+                            println!("🏓 Reply from {name}: seq={seq} time={ms:.2} ms");
+                        }
+                        times.push(ms);
+                    }
+                    Err(e) => {
+                        error!("Failed to ping apprentice: {}", e);
+                        if quiet {
+                            eprintln!("Failed to ping {name}: {e}");
+                        }
+                        status!(quiet, "💥 No reply from {name}: seq={seq}");
+                    }
+                }
+            }
+            if count > 1 && !times.is_empty() {
+                let min = times.iter().cloned().fold(f64::INFINITY, f64::min);
+                let max = times.iter().cloned().fold(0.0, f64::max);
+                let avg = times.iter().sum::<f64>() / times.len() as f64;
+                status!(
+                    quiet,
+                    "📊 {} of {count} replied; min/avg/max = {min:.2}/{avg:.2}/{max:.2} ms",
+                    times.len()
+                );
+            }
+            if times.len() < count.max(1) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Logs {
+            name,
+            tail,
+            since,
+            follow,
+        } => {
+            use std::io::Write;
+
+            let since = match since {
+                Some(since) => chrono::DateTime::parse_from_rfc3339(&since)
+                    .map_err(|e| anyhow::anyhow!("Invalid --since timestamp {since}: {e}"))?
+                    .timestamp(),
+                None => 0,
+            };
+
+            let mut logs = Box::pin(
+                sorcerer
+                    .container_log_stream(&name, tail, since, follow)
+                    .await?,
+            );
+            loop {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => break,
+                    chunk = logs.next() => match chunk {
+                        Some(chunk) => {
+                            print!("{}", chunk?);
+                            std::io::stdout().flush()?;
+                        }
+                        None => break,
+                    },
+                }
+            }
+        }
+        Commands::Serve { port } => {
+            status!(
+                quiet,
+                "🏰 Serving the sorcerer API on http://127.0.0.1:{port}..."
+            );
+            serve::serve(sorcerer, port).await?;
+        }
+        Commands::Sessions { name } => {
+            status!(quiet, "🧵 Listing sessions for apprentice {name}...");
+            match sorcerer.list_sessions(&name).await {
+                Ok(sessions) => {
+                    if sessions.is_empty() {
+                        status!(quiet, "No sessions found for apprentice {name}.");
+                    } else {
+                        for session in sessions {
+                            println!("{session}");
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to list sessions: {}", e);
+                    if quiet {
+                        eprintln!("Failed to list sessions for {name}: {e}");
+                    }
+                    status!(quiet, "💥 Failed to list sessions for {name}");
                 }
             }
         }
@@ -207,6 +1300,486 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+fn format_spell_time(time: &str) -> String {
+    // Parse and format timestamp to be shorter
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(time) {
+        dt.format("%Y-%m-%d %H:%M:%S").to_string()
+    } else {
+        time.to_string()
+    }
+}
+
+fn print_status_box(name: &str, status: &sorcerer::spells::StatusResponse) {
+    // Calculate box width based on apprentice name length
+    let min_width = 45;
+    let name_header = format!(" Apprentice: {name} ");
+    let box_width = min_width.max(name_header.len() + 2);
+
+    // Draw apprentice info box
+    println!("┌─{}─┐", name_header.pad_to_width(box_width - 4, '─'));
+    let state = if status.draining {
+        format!("{} (draining)", status.state)
+    } else {
+        status.state.clone()
+    };
+    println!("│ State: {:<width$} │", state, width = box_width - 11);
+    if !status.last_spell_time.is_empty() {
+        let last_msg = format!(
+            "Last Message: {}",
+            format_spell_time(&status.last_spell_time)
+        );
+        println!("│ {:<width$} │", last_msg, width = box_width - 4);
+    }
+    if !status.error.is_empty() {
+        let mut error = format!("Error: {}", status.error.replace('\n', " "));
+        let kept = text::truncate_on_char_boundary(&error, box_width - 5).len();
+        if kept < error.len() {
+            error.truncate(kept);
+            error.push('…');
+        }
+        println!("│ {:<width$} │", error, width = box_width - 4);
+    }
+    if let Some(budget) = status.spell_budget {
+        let budget = format!("Spell Budget: {budget} left this minute");
+        println!("│ {:<width$} │", budget, width = box_width - 4);
+    }
+    println!("└{}┘", "─".repeat(box_width - 2));
+}
+
+/// Redraw the apprentice status boxes every `interval` seconds until Ctrl-C.
+async fn run_top(sorcerer: &sorcerer::Sorcerer, interval: u64) -> Result<()> {
+    use std::io::Write;
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut resize = signal(SignalKind::window_change())?;
+
+    // Draw on the alternate screen so the user's scrollback is left intact
+    print!("\x1b[?1049h\x1b[?25l");
+    let result = loop {
+        let statuses = match sorcerer.get_all_status().await {
+            Ok(statuses) => statuses,
+            Err(e) => break Err(e),
+        };
+        let mut statuses: Vec<_> = statuses.into_iter().collect();
+        statuses.sort_by(|a, b| a.0.cmp(&b.0));
+
+        // Move home and clear, then redraw the whole frame
+        print!("\x1b[H\x1b[2J");
+        println!("🧙 Apprentices (refreshing every {interval}s, Ctrl-C to exit)\n");
+        if statuses.is_empty() {
+            println!("No apprentices found.");
+        }
+        for (name, status) in &statuses {
+            print_status_box(name, status);
+        }
+        std::io::stdout().flush()?;
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break Ok(()),
+            _ = resize.recv() => {}
+            _ = tokio::time::sleep(std::time::Duration::from_secs(interval)) => {}
+        }
+    };
+    print!("\x1b[?25h\x1b[?1049l");
+    std::io::stdout().flush()?;
+
+    result
+}
+
+/// ANSI colors cycled through to tell apprentices apart in `follow`.
+const FOLLOW_COLORS: &[&str] = &["36", "35", "33", "32", "34", "31"];
+
+/// Poll chat histories and print each new line prefixed with its apprentice's
+/// name, until Ctrl-C. With no names, every apprentice is followed. Lines that
+/// were already there when following began aren't printed.
+async fn run_follow(
+    sorcerer: &sorcerer::Sorcerer,
+    names: &[String],
+    session: Option<&str>,
+    interval: u64,
+) -> Result<()> {
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let mut seen: HashMap<String, Vec<String>> = HashMap::new();
+    let mut colors: HashMap<String, &str> = HashMap::new();
+    let mut first_poll = true;
+
+    loop {
+        let targets = if names.is_empty() {
+            sorcerer.list_apprentices().await?
+        } else {
+            names.to_vec()
+        };
+        for name in &targets {
+            // Apprentices that can't be reached are picked up once they answer
+            let Ok(history) = sorcerer.get_chat_history(name, 0, session).await else {
+                continue;
+            };
+            let previous = seen.entry(name.clone()).or_default();
+            if !first_poll {
+                let next_color = FOLLOW_COLORS[colors.len() % FOLLOW_COLORS.len()];
+                let code = *colors.entry(name.clone()).or_insert(next_color);
+                for line in new_history_lines(previous, &history) {
+                    if color {
+                        println!("\x1b[1;{code}m[{name}]\x1b[0m {line}");
+                    } else {
+                        println!("[{name}] {line}");
+                    }
+                }
+            }
+            *previous = history;
+        }
+        first_poll = false;
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(interval)) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// The lines of `current` that weren't in `previous`. Histories are capped, so
+/// the oldest lines may have been dropped from the front since the last poll.
+fn new_history_lines<'a>(previous: &[String], current: &'a [String]) -> &'a [String] {
+    (0..=previous.len())
+        .map(|start| &previous[start..])
+        .find(|overlap| current.starts_with(overlap))
+        .map_or(current, |overlap| &current[overlap.len()..])
+}
+
+/// Cast a spell with a spinner running, then print the reply or the failure.
+/// Returns whether the apprentice replied.
+async fn cast_and_print(
+    sorcerer: &sorcerer::Sorcerer,
+    name: &str,
+    message: &str,
+    options: &sorcerer::SpellOptions,
+    output: OutputFormat,
+    retries: u32,
+    verbosity: Verbosity,
+) -> bool {
+    let quiet = verbosity == Verbosity::Quiet;
+    let spinner = if quiet {
+        None
+    } else {
+        start_spinner("The apprentice is pondering...")
+    };
+    let mut attempt = 0;
+    let outcome = loop {
+        match sorcerer.cast_spell_detailed(name, message, options).await {
+            Err(e) if attempt < retries && sorcerer::is_connection_error(&e) => {
+                attempt += 1;
+                warn!("Lost the connection to {}, retrying: {}", name, e);
+                if let Some(spinner) = &spinner {
+                    spinner.println("  retrying...");
+                }
+            }
+            outcome => break outcome,
+        }
+    };
+    if let Some(spinner) = spinner {
+        spinner.finish_and_clear();
+    }
+
+    if output == OutputFormat::Json {
+        return print_spell_json(name, outcome);
+    }
+    let result = outcome.and_then(|outcome| {
+        if outcome.success {
+            Ok(outcome)
+        } else {
+            Err(anyhow::anyhow!("Tell failed: {}", outcome.error))
+        }
+    });
+    let quiet = quiet || output == OutputFormat::Plain;
+    match result {
+        Ok(outcome) => {
+            status!(quiet, "🔮 The apprentice responds:");
+            println!("{}", outcome.response);
+            if outcome.truncated {
+                warn!("Reply from {} was truncated at the token limit", name);
+                if quiet {
+                    eprintln!("Reply from {name} was cut off at the token limit");
+                }
+                status!(quiet, "✂️  The reply was cut off at the token limit");
+            }
+            if verbosity == Verbosity::Verbose && !quiet {
+                print_spell_footer(&outcome);
+            }
+            true
+        }
+        Err(e) => {
+            error!("Message sending failed: {}", e);
+            if quiet {
+                eprintln!("Message to {name} failed: {e}");
+            }
+            status!(quiet, "💥 The message failed: {e}");
+            false
+        }
+    }
+}
+
+/// How long a batch step took, as ", 1.8s" to append to its progress count
+/// under `--verbose`; empty otherwise.
+fn timing_note(verbose: bool, elapsed: std::time::Duration) -> String {
+    if verbose {
+        format!(", {:.1}s", elapsed.as_secs_f64())
+    } else {
+        String::new()
+    }
+}
+
+/// Print a dim `(model · 1.8s · 412→190 tokens)` line under a reply for `--verbose`.
+fn print_spell_footer(outcome: &sorcerer::SpellOutcome) {
+    let model = if outcome.model.is_empty() {
+        "unknown model"
+    } else {
+        &outcome.model
+    };
+    let footer = format!(
+        "({model} · {:.1}s · {}→{} tokens)",
+        outcome.latency.as_secs_f64(),
+        outcome.input_tokens,
+        outcome.output_tokens
+    );
+    if std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+        println!("\x1b[2m{footer}\x1b[0m");
+    } else {
+        println!("{footer}");
+    }
+}
+
+/// Print a spell's outcome as a single JSON object for `tell --output json`.
+/// Returns whether the apprentice replied.
+fn print_spell_json(name: &str, outcome: Result<sorcerer::SpellOutcome>) -> bool {
+    let succeeded = outcome.as_ref().is_ok_and(|outcome| outcome.success);
+    let value = match outcome {
+        Ok(outcome) => serde_json::json!({
+            "spell_id": outcome.spell_id,
+            "success": outcome.success,
+            "response": outcome.success.then_some(&outcome.response),
+            "error": (!outcome.success).then_some(&outcome.error),
+            "model": (!outcome.model.is_empty()).then_some(&outcome.model),
+            "truncated": outcome.truncated,
+            "tokens": {
+                "input": outcome.input_tokens,
+                "output": outcome.output_tokens,
+            },
+            "latency_ms": outcome.latency.as_millis() as u64,
+        }),
+        Err(e) => {
+            error!("Message sending failed: {}", e);
+            serde_json::json!({
+                "spell_id": null,
+                "success": false,
+                "response": null,
+                "error": format!("Message to {name} failed: {e}"),
+                "model": null,
+                "truncated": false,
+                "tokens": null,
+                "latency_ms": null,
+            })
+        }
+    };
+    println!("{value}");
+    succeeded
+}
+
+/// How long `tell --watch` waits for a burst of file changes to settle.
+const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// The message `tell --watch` resends; attachments are re-read on every send.
+struct WatchedMessage<'a> {
+    name: &'a str,
+    message: &'a str,
+    attachments: &'a [std::path::PathBuf],
+    options: &'a sorcerer::SpellOptions,
+    output: OutputFormat,
+    retries: u32,
+    verbose: bool,
+}
+
+/// Resend a message each time files under `dir` change, until Ctrl-C.
+async fn tell_on_change(
+    sorcerer: &sorcerer::Sorcerer,
+    request: &WatchedMessage<'_>,
+    dir: &std::path::Path,
+    quiet: bool,
+) -> Result<()> {
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    let root = dir
+        .canonicalize()
+        .with_context(|| format!("Cannot watch {}", dir.display()))?;
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let watched_root = root.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        let is_change = matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        );
+        // Ignore churn in hidden paths such as .git or editor swap files
+        if is_change && event.paths.iter().any(|p| !is_hidden(p, &watched_root)) {
+            let _ = tx.send(());
+        }
+    })?;
+    watcher.watch(&root, RecursiveMode::Recursive)?;
+    status!(
+        quiet,
+        "👀 Watching {} for changes (Ctrl-C to stop)...",
+        root.display()
+    );
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        tokio::select! {
+            _ = &mut ctrl_c => break,
+            event = rx.recv() => if event.is_none() { break },
+        }
+        let resend = async {
+            tokio::time::sleep(WATCH_DEBOUNCE).await;
+            while rx.try_recv().is_ok() {}
+            status!(
+                quiet,
+                "🔁 Changes detected, resending to {}...",
+                request.name
+            );
+            match with_attachments(request.message, request.attachments) {
+                Ok(message) => {
+                    cast_and_print(
+                        sorcerer,
+                        request.name,
+                        &message,
+                        request.options,
+                        request.output,
+                        request.retries,
+                        Verbosity::new(quiet, request.verbose),
+                    )
+                    .await;
+                }
+                Err(e) => {
+                    error!("Message sending failed: {}", e);
+                    if quiet {
+                        eprintln!("Message to {} failed: {e}", request.name);
+                    }
+                    status!(quiet, "💥 The message failed: {e}");
+                }
+            }
+            // Drop anything written while the apprentice was working so its
+            // own output can't trigger another round
+            while rx.try_recv().is_ok() {}
+        };
+        tokio::select! {
+            _ = &mut ctrl_c => break,
+            _ = resend => {}
+        }
+    }
+
+    status!(quiet, "👋 Stopped watching {}", root.display());
+    Ok(())
+}
+
+fn is_hidden(path: &std::path::Path, root: &std::path::Path) -> bool {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .any(|c| {
+            c.as_os_str()
+                .to_str()
+                .is_some_and(|name| name.starts_with('.'))
+        })
+}
+
+/// Accept `KEY=VALUE` with a non-empty key that is a valid variable name.
+fn parse_env_var(value: &str) -> Result<String, String> {
+    let (key, _) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got {value:?}"))?;
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("invalid environment variable name {key:?}"));
+    }
+    Ok(value.to_string())
+}
+
+/// Upper bound on attached file contents, to protect the token budget.
+const MAX_ATTACHMENT_CHARS: usize = 100_000;
+
+/// Prepend each attached file as a labelled, fenced block ahead of the message.
+fn with_attachments(message: &str, attachments: &[std::path::PathBuf]) -> Result<String> {
+    if attachments.is_empty() {
+        return Ok(message.to_string());
+    }
+
+    let mut context = String::new();
+    for path in attachments {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read attachment {}: {}", path.display(), e))?;
+        context.push_str(&format!("File: {}\n```\n{}", path.display(), contents));
+        if !contents.ends_with('\n') {
+            context.push('\n');
+        }
+        context.push_str("```\n\n");
+    }
+
+    let cut = text::truncate_on_char_boundary(&context, MAX_ATTACHMENT_CHARS).len();
+    if cut < context.len() {
+        eprintln!(
+            "⚠️  Attachments truncated to {MAX_ATTACHMENT_CHARS} characters ({} dropped)",
+            context[cut..].chars().count()
+        );
+        context.truncate(cut);
+        context.push_str("\n…[attachments truncated]\n\n");
+    }
+
+    Ok(format!("{context}{message}"))
+}
+
+/// Animate a spinner on stderr while waiting, unless stderr isn't a terminal
+/// or the user has asked for plain output via `NO_COLOR`.
+fn start_spinner(message: &'static str) -> Option<ProgressBar> {
+    if !std::io::stderr().is_terminal() || std::env::var_os("NO_COLOR").is_some() {
+        return None;
+    }
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(
+        ProgressStyle::with_template("{spinner} {msg} ({elapsed})")
+            .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+    );
+    spinner.set_message(message);
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    Some(spinner)
+}
+
+/// Render a history entry through a `--format` template or one of its named
+/// presets. The sorcerer's turns are labelled "Sorcerer", the apprentice's with
+/// its name.
+fn format_history_entry(
+    format: &str,
+    apprentice_name: &str,
+    entry: &sorcerer::spells::HistoryTurn,
+) -> String {
+    let template = match format {
+        "plain" => "{role}: {text}",
+        "markdown" => "**{role}:** {text}",
+        "slack" => "*{role}*: {text}",
+        template => template,
+    };
+    let role = if entry.role == "user" {
+        "Sorcerer"
+    } else {
+        apprentice_name
+    };
+
+    template
+        .replace("{role}", role)
+        .replace("{time}", &entry.timestamp)
+        .replace("{text}", &entry.content)
+}
+
 fn print_wrapped_chat_line(line: &str) {
     // Apply formatting to chat lines with bold usernames and mild colors
     for line_part in line.lines() {
@@ -0,0 +1,191 @@
+use crate::sorcerer::{ApprenticeNotFound, InvalidRequest, Sorcerer, SpellOptions, SummonOptions};
+use anyhow::Result;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Wraps sorcerer errors so handlers can use `?` and reply with a JSON body:
+/// 404 for unknown apprentices, 400 for invalid requests, 500 otherwise.
+struct ApiError(anyhow::Error);
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        Self(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        error!("Request failed: {}", self.0);
+        let status = if self.0.chain().any(|cause| cause.is::<ApprenticeNotFound>()) {
+            StatusCode::NOT_FOUND
+        } else if self.0.chain().any(|cause| cause.is::<InvalidRequest>()) {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        (status, Json(json!({ "error": self.0.to_string() }))).into_response()
+    }
+}
+
+type ApiResult = Result<Json<Value>, ApiError>;
+
+#[derive(Deserialize)]
+struct SummonBody {
+    name: String,
+    model: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TellBody {
+    message: String,
+    model: Option<String>,
+    session: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    lines: Option<usize>,
+    session: Option<String>,
+}
+
+/// Spell latency buckets in seconds; Claude replies take from about a second
+/// to a few minutes.
+const SPELL_DURATION_BUCKETS: &[f64] = &[0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0];
+
+/// Install the Prometheus recorder that backs `/metrics`.
+fn install_metrics() -> Result<PrometheusHandle> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full("sorcerer_spell_duration_seconds".to_string()),
+            SPELL_DURATION_BUCKETS,
+        )?
+        .install_recorder()?;
+
+    metrics::describe_gauge!("sorcerer_apprentices", "Apprentices currently summoned");
+    metrics::describe_counter!("sorcerer_spells_total", "Messages sent to apprentices");
+    metrics::describe_counter!("sorcerer_tokens_total", "Claude tokens used by messages");
+    metrics::describe_counter!("sorcerer_summons_total", "Apprentice summon attempts");
+    metrics::describe_counter!("sorcerer_kills_total", "Apprentice kill attempts");
+    metrics::describe_histogram!(
+        "sorcerer_spell_duration_seconds",
+        metrics::Unit::Seconds,
+        "Time for an apprentice to answer a message"
+    );
+
+    Ok(handle)
+}
+
+/// Serve the sorcerer's operations as a JSON REST API until Ctrl-C.
+pub async fn serve(sorcerer: Sorcerer, port: u16) -> Result<()> {
+    let metrics = install_metrics()?;
+    let app = Router::new()
+        .route("/apprentices", get(list).post(summon))
+        .route("/apprentices/:name", delete(kill))
+        .route("/apprentices/:name/spells", post(tell))
+        .route("/apprentices/:name/history", get(history))
+        .route("/status", get(status))
+        .route("/metrics", get(render_metrics))
+        .layer(Extension(metrics))
+        .with_state(Arc::new(sorcerer));
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    info!("Serving the sorcerer API on http://{}", addr);
+
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+            info!("Shutting down the sorcerer API");
+        })
+        .await?;
+
+    Ok(())
+}
+
+async fn list(State(sorcerer): State<Arc<Sorcerer>>) -> ApiResult {
+    let apprentices = sorcerer.list_apprentices().await?;
+    Ok(Json(json!({ "apprentices": apprentices })))
+}
+
+async fn summon(State(sorcerer): State<Arc<Sorcerer>>, Json(body): Json<SummonBody>) -> ApiResult {
+    let options = SummonOptions {
+        model: body.model,
+        ..Default::default()
+    };
+    sorcerer.summon_apprentice(&body.name, &options).await?;
+    Ok(Json(json!({ "name": body.name })))
+}
+
+async fn kill(State(sorcerer): State<Arc<Sorcerer>>, Path(name): Path<String>) -> ApiResult {
+    sorcerer.kill_apprentice(&name, false).await?;
+    Ok(Json(json!({ "name": name })))
+}
+
+async fn tell(
+    State(sorcerer): State<Arc<Sorcerer>>,
+    Path(name): Path<String>,
+    Json(body): Json<TellBody>,
+) -> ApiResult {
+    let options = SpellOptions {
+        model: body.model,
+        session: body.session,
+        ..Default::default()
+    };
+    let response = sorcerer.cast_spell(&name, &body.message, &options).await?;
+    Ok(Json(json!({ "name": name, "response": response })))
+}
+
+async fn history(
+    State(sorcerer): State<Arc<Sorcerer>>,
+    Path(name): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> ApiResult {
+    let history = sorcerer
+        .get_chat_history(&name, query.lines.unwrap_or(0), query.session.as_deref())
+        .await?;
+    Ok(Json(json!({ "name": name, "history": history })))
+}
+
+async fn render_metrics(
+    State(sorcerer): State<Arc<Sorcerer>>,
+    Extension(metrics): Extension<PrometheusHandle>,
+) -> Result<impl IntoResponse, ApiError> {
+    let apprentices = sorcerer.list_apprentices().await?;
+    metrics::gauge!("sorcerer_apprentices").set(apprentices.len() as f64);
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics.render(),
+    ))
+}
+
+async fn status(State(sorcerer): State<Arc<Sorcerer>>) -> ApiResult {
+    let statuses: serde_json::Map<String, Value> = sorcerer
+        .get_all_status()
+        .await?
+        .into_iter()
+        .map(|(name, status)| {
+            (
+                name,
+                json!({
+                    "state": status.state,
+                    "error": (!status.error.is_empty()).then_some(&status.error),
+                    "last_spell_time": status.last_spell_time,
+                    "spell_budget": status.spell_budget,
+                    "draining": status.draining,
+                }),
+            )
+        })
+        .collect();
+    Ok(Json(Value::Object(statuses)))
+}
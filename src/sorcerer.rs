@@ -7,7 +7,9 @@ use bollard::{
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tonic::transport::Channel;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::service::{interceptor::InterceptedService, Interceptor};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint};
 use tracing::{info, warn};
 
 pub mod spells {
@@ -15,19 +17,230 @@ pub mod spells {
 }
 
 use spells::apprentice_client::ApprenticeClient;
-use spells::{ChatHistoryRequest, SpellRequest, StatusRequest};
+use spells::{
+    AppendHistoryRequest, CancelSpellRequest, ChatHistoryRequest, ConfigRequest, DrainRequest,
+    GetSystemPromptRequest, HealthRequest, IdentityRequest, LastIncantationRequest,
+    ListSessionsRequest, PingRequest, SetSystemPromptRequest, SpellRequest, StatusRequest,
+};
+
+/// Number of ports above `starting_port` that hashed port assignment draws from.
+const HASHED_PORT_RANGE: u16 = 1000;
+
+/// How long a graceful kill waits for the Kill RPC and for the container to stop
+/// before falling back to a forced removal.
+const KILL_STEP_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(15);
+
+/// How long `find_reapable` waits for a running apprentice to answer before
+/// calling it unresponsive.
+const REAP_PROBE_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(10);
+
+/// How long `drain_and_wait` lets the spell in flight run before giving up.
+const DRAIN_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(300);
+
+const TLS_CERT_MOUNT: &str = "/etc/apprentice/tls/server.crt";
+const TLS_KEY_MOUNT: &str = "/etc/apprentice/tls/server.key";
+
+/// The digest an image reference is pinned to, e.g. `sha256:abc...` for
+/// `sorcerer-apprentice@sha256:abc...`, or None for a tag like `:latest`.
+pub fn pinned_digest(image: &str) -> Option<&str> {
+    image
+        .split_once('@')
+        .map(|(_, digest)| digest)
+        .filter(|digest| digest.starts_with("sha256:"))
+}
+
+/// Whether a spell failed because the apprentice couldn't be reached, as opposed
+/// to the apprentice or Claude reporting an error. Only the former is worth
+/// retrying: `Cancelled` or `Unknown` can arrive after the apprentice already
+/// sent the spell to Claude, and a retry would bill it twice.
+pub fn is_connection_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.is::<tonic::transport::Error>()
+            || cause
+                .downcast_ref::<tonic::Status>()
+                .is_some_and(|status| status.code() == tonic::Code::Unavailable)
+    })
+}
+
+/// Count an operation's outcome for the `/metrics` endpoint of `srcrr serve`.
+/// Without an installed recorder this does nothing.
+fn record_outcome<T, E>(metric: &'static str, result: &std::result::Result<T, E>) {
+    let outcome = if result.is_ok() { "success" } else { "error" };
+    metrics::counter!(metric, "result" => outcome).increment(1);
+}
+
+/// Attaches the shared-secret bearer token to every outgoing request.
+#[derive(Clone)]
+pub struct AuthInterceptor {
+    token: Option<MetadataValue<Ascii>>,
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(
+        &mut self,
+        mut request: tonic::Request<()>,
+    ) -> Result<tonic::Request<()>, tonic::Status> {
+        if let Some(token) = &self.token {
+            request
+                .metadata_mut()
+                .insert("authorization", token.clone());
+        }
+        Ok(request)
+    }
+}
+
+pub type ApprenticeConnection = ApprenticeClient<InterceptedService<Channel, AuthInterceptor>>;
 
 pub struct Apprentice {
     pub _name: String,
     pub container_id: String,
     pub _port: u16,
-    pub client: Option<ApprenticeClient<Channel>>,
+    /// Container image the apprentice was summoned from
+    pub image: String,
+    pub client: Option<ApprenticeConnection>,
+}
+
+/// One row of `srcrr list --long`.
+pub struct ApprenticeInfo {
+    pub name: String,
+    /// The apprentice's reported state, or "disconnected" if it can't be reached
+    pub state: String,
+    pub port: u16,
+    pub container_id: String,
+    pub image: String,
+    /// Registry digest (`sha256:...`) of the image, if known. Only looked up by
+    /// `list_apprentices_detailed`, since it costs a runtime call per image.
+    pub image_digest: Option<String>,
+}
+
+/// An apprentice container `srcrr reap` would remove.
+pub struct ReapCandidate {
+    pub name: String,
+    pub container_id: String,
+    /// The container's state, "exited" or "dead", or "unresponsive" if it is
+    /// running but didn't answer a health probe
+    pub reason: String,
+}
+
+/// The full result of one spell, as reported by `tell --output json`.
+pub struct SpellOutcome {
+    pub spell_id: String,
+    pub success: bool,
+    pub response: String,
+    pub error: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    /// Model that answered; empty if the spell never reached Claude
+    pub model: String,
+    /// The reply was cut off at Claude's token limit
+    pub truncated: bool,
+    /// Time from sending the spell to receiving the reply
+    pub latency: std::time::Duration,
+}
+
+/// Where `summon --dry-run` would put a new apprentice.
+pub struct SummonPlan {
+    pub name: String,
+    pub container_name: String,
+    pub port: u16,
+    pub image: String,
+}
+
+/// How `summon --wait-healthy` polls a new apprentice until it is ready.
+#[derive(Clone, Copy)]
+pub struct HealthWait {
+    pub interval: std::time::Duration,
+    pub deadline: std::time::Duration,
+}
+
+/// Returned (in the error chain) when an apprentice isn't healthy before its
+/// `HealthWait` deadline, so callers can tell a timeout from other failures.
+#[derive(Debug)]
+pub struct HealthDeadlineElapsed {
+    pub deadline: std::time::Duration,
+    pub last_error: String,
+}
+
+impl std::fmt::Display for HealthDeadlineElapsed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Apprentice was not healthy within {}s: {}",
+            self.deadline.as_secs(),
+            self.last_error
+        )
+    }
+}
+
+impl std::error::Error for HealthDeadlineElapsed {}
+
+/// Returned (in the error chain) when a command names an apprentice the
+/// sorcerer doesn't know, so `serve` can answer 404.
+#[derive(Debug)]
+pub struct ApprenticeNotFound(pub String);
+
+impl std::fmt::Display for ApprenticeNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Apprentice {} not found", self.0)
+    }
+}
+
+impl std::error::Error for ApprenticeNotFound {}
+
+/// Returned (in the error chain) when a request is refused before reaching an
+/// apprentice, e.g. for a bad name or an empty message, so `serve` can answer 400.
+#[derive(Debug)]
+pub struct InvalidRequest(pub String);
+
+impl std::fmt::Display for InvalidRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InvalidRequest {}
+
+fn not_found(name: &str) -> anyhow::Error {
+    ApprenticeNotFound(name.to_string()).into()
+}
+
+/// Per-spell settings forwarded to the apprentice alongside the incantation.
+#[derive(Default)]
+pub struct SpellOptions {
+    pub model: Option<String>,
+    pub session: Option<String>,
+    /// Send the incantation even if it is empty or only whitespace
+    pub allow_empty: bool,
+}
+
+/// Per-apprentice container settings applied at summon time.
+#[derive(Default)]
+pub struct SummonOptions {
+    pub model: Option<String>,
+    pub memory: Option<i64>,
+    pub nano_cpus: Option<i64>,
+    /// Container image to use instead of the configured default
+    pub image: Option<String>,
+    /// Poll readiness on a fixed interval up to a deadline, instead of the
+    /// connect retry settings
+    pub health_wait: Option<HealthWait>,
+    /// Extra `KEY=VALUE` entries for the container environment
+    pub env: Vec<String>,
+    /// Leave the container in place if the apprentice fails to start, instead
+    /// of removing it
+    pub keep_on_failure: bool,
+    /// Instructions added after the apprentice's system prompt
+    pub prompt_append: Option<String>,
+    /// Extra `HOST:CONTAINER[:ro]` bind mounts
+    pub mounts: Vec<String>,
 }
 
 pub struct Sorcerer {
     docker: Docker,
     apprentices: Arc<Mutex<HashMap<String, Apprentice>>>,
     next_port: Arc<Mutex<u16>>,
+    /// Apprentices whose containers are still starting, with the port reserved for each
+    summoning: Arc<Mutex<HashMap<String, u16>>>,
     config: AppConfig,
 }
 
@@ -40,7 +253,130 @@ impl Sorcerer {
                 .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
     }
 
-    async fn connect_to_container_runtime() -> Result<Docker> {
+    /// Refuse blank incantations before they cost an API call. Messages made of
+    /// other unicode, such as zero-width characters, still go through.
+    fn check_incantation(name: &str, incantation: &str) -> Result<()> {
+        if incantation.is_empty() {
+            Err(InvalidRequest(format!(
+                "Refusing to send an empty message to {}; pass --allow-empty to send it anyway",
+                name
+            ))
+            .into())
+        } else if incantation.trim().is_empty() {
+            Err(InvalidRequest(format!(
+                "Refusing to send a message to {} that is only whitespace; pass --allow-empty to send it anyway",
+                name
+            ))
+            .into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn apprentice_endpoint(&self, port: u16) -> Result<Endpoint> {
+        let ca = self
+            .config
+            .tls_ca
+            .as_ref()
+            .filter(|_| self.config.tls_enabled());
+        let endpoint = match ca {
+            Some(ca_path) => {
+                let ca = std::fs::read_to_string(ca_path)
+                    .map_err(|e| anyhow!("Failed to read TLS CA {}: {}", ca_path, e))?;
+                let tls = ClientTlsConfig::new()
+                    .ca_certificate(Certificate::from_pem(ca))
+                    .domain_name(self.config.tls_domain.clone());
+                Endpoint::from_shared(format!("https://127.0.0.1:{port}"))?.tls_config(tls)?
+            }
+            None => Endpoint::from_shared(format!("http://127.0.0.1:{port}"))?,
+        };
+        // Fail fast on wedged containers instead of stalling startup
+        let endpoint = endpoint.connect_timeout(std::time::Duration::from_secs(
+            self.config.connect_timeout_secs,
+        ));
+
+        // Keep idle connections alive so long-running `serve` doesn't go stale
+        if self.config.keepalive_interval_secs == 0 {
+            return Ok(endpoint);
+        }
+        Ok(endpoint
+            .http2_keep_alive_interval(std::time::Duration::from_secs(
+                self.config.keepalive_interval_secs,
+            ))
+            .keep_alive_timeout(std::time::Duration::from_secs(
+                self.config.keepalive_timeout_secs,
+            ))
+            .keep_alive_while_idle(true))
+    }
+
+    fn auth_interceptor(&self) -> Result<AuthInterceptor> {
+        let token = match &self.config.auth_token {
+            Some(token) => Some(
+                format!("Bearer {token}")
+                    .parse()
+                    .map_err(|_| anyhow!("SORCERER_TOKEN contains invalid characters"))?,
+            ),
+            None => None,
+        };
+        Ok(AuthInterceptor { token })
+    }
+
+    async fn connect_with_retry(
+        endpoint: Endpoint,
+        auth: AuthInterceptor,
+        attempts: u32,
+        delay: tokio::time::Duration,
+    ) -> Result<ApprenticeConnection> {
+        let mut attempt = 1;
+        let mut delay = delay;
+        loop {
+            match endpoint.connect().await {
+                Ok(channel) => return Ok(ApprenticeClient::with_interceptor(channel, auth)),
+                Err(e) if attempt < attempts => {
+                    info!(
+                        "Connection to {} failed (attempt {}/{}): {}",
+                        endpoint.uri(),
+                        attempt,
+                        attempts,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    async fn connect_to_container_runtime(runtime: &str) -> Result<Docker> {
+        match runtime {
+            "auto" => {}
+            "podman" => {
+                return Self::connect_to_podman().await.ok_or_else(|| {
+                    anyhow!(
+                        "SORCERER_RUNTIME is podman, but no Podman socket is responding.\n  \
+                             For Podman: systemctl --user start podman.socket"
+                    )
+                })
+            }
+            "docker" => return Self::connect_to_docker().await,
+            other => {
+                return Err(anyhow!(
+                    "Unknown SORCERER_RUNTIME {}; expected podman, docker or auto",
+                    other
+                ))
+            }
+        }
+
+        // Try Podman first, then fall back to Docker
+        match Self::connect_to_podman().await {
+            Some(docker) => Ok(docker),
+            None => Self::connect_to_docker().await,
+        }
+    }
+
+    async fn connect_to_podman() -> Option<Docker> {
         // Try Podman socket first (rootless)
         if let Ok(socket_path) = std::env::var("XDG_RUNTIME_DIR") {
             let podman_socket = format!("unix://{socket_path}/podman/podman.sock");
@@ -49,8 +385,8 @@ impl Sorcerer {
             {
                 match docker.ping().await {
                     Ok(_) => {
-                        info!("Connected to Podman (rootless)");
-                        return Ok(docker);
+                        info!("Connected to Podman (rootless) at {}", podman_socket);
+                        return Some(docker);
                     }
                     Err(_) => info!("Podman socket found but not responding"),
                 }
@@ -64,18 +400,25 @@ impl Sorcerer {
         {
             match docker.ping().await {
                 Ok(_) => {
-                    info!("Connected to Podman (system)");
-                    return Ok(docker);
+                    info!("Connected to Podman (system) at {}", system_podman_socket);
+                    return Some(docker);
                 }
                 Err(_) => info!("System Podman socket found but not responding"),
             }
         }
 
-        // Fall back to Docker
+        None
+    }
+
+    async fn connect_to_docker() -> Result<Docker> {
         match Docker::connect_with_local_defaults() {
             Ok(docker) => match docker.ping().await {
                 Ok(_) => {
-                    info!("Connected to Docker");
+                    info!(
+                        "Connected to Docker at {}",
+                        std::env::var("DOCKER_HOST")
+                            .unwrap_or_else(|_| "unix:///var/run/docker.sock".to_string())
+                    );
                     Ok(docker)
                 }
                 Err(e) => Err(anyhow!("Cannot reach Docker daemon. Make sure Docker is running.\n  Error: {}", e)),
@@ -89,14 +432,16 @@ impl Sorcerer {
     }
 
     pub async fn new() -> Result<Self> {
-        let docker = Self::connect_to_container_runtime().await?;
         let config = AppConfig::default();
+        config.check_tls()?;
+        let docker = Self::connect_to_container_runtime(&config.runtime).await?;
         let starting_port = config.starting_port;
 
-        let mut sorcerer = Self {
+        let sorcerer = Self {
             docker,
             apprentices: Arc::new(Mutex::new(HashMap::new())),
             next_port: Arc::new(Mutex::new(starting_port)),
+            summoning: Arc::new(Mutex::new(HashMap::new())),
             config,
         };
 
@@ -106,7 +451,7 @@ impl Sorcerer {
         Ok(sorcerer)
     }
 
-    async fn discover_apprentices(&mut self) -> Result<()> {
+    async fn discover_apprentices(&self) -> Result<()> {
         use bollard::container::ListContainersOptions;
 
         let mut filters = HashMap::new();
@@ -119,198 +464,1017 @@ impl Sorcerer {
         });
 
         let containers = self.docker.list_containers(options).await?;
+
+        // Inspect and connect to every container at once, without holding any
+        // locks, so a wedged apprentice can't hold up every command's startup
+        let discovered =
+            futures_util::future::join_all(containers.iter().filter_map(|container| {
+                let name = container
+                    .names
+                    .as_ref()?
+                    .iter()
+                    .find_map(|name| name.strip_prefix("/apprentice-"))?;
+                Some(self.discover_apprentice(name, container))
+            }))
+            .await;
+
         let mut apprentices = self.apprentices.lock().await;
         let mut next_port = self.next_port.lock().await;
+        for discovered in discovered {
+            let apprentice = discovered?;
+
+            // Update next_port to avoid conflicts
+            if apprentice._port >= *next_port {
+                *next_port = apprentice._port + 1;
+            }
+
+            info!(
+                "Discovered apprentice: {} (port: {})",
+                apprentice._name, apprentice._port
+            );
+            apprentices.insert(apprentice._name.clone(), apprentice);
+        }
+
+        Ok(())
+    }
 
+    /// Work out an existing container's port and, if it is running, connect to
+    /// it with the usual retries.
+    async fn discover_apprentice(
+        &self,
+        name: &str,
+        container: &bollard::models::ContainerSummary,
+    ) -> Result<Apprentice> {
+        let container_id = container.id.clone().unwrap_or_default();
+        let container_info = self
+            .docker
+            .inspect_container(&container_id, None)
+            .await
+            .ok();
+        let port = container_info
+            .as_ref()
+            .and_then(|info| info.config.as_ref())
+            .and_then(|config| config.env.as_ref())
+            .and_then(|env| {
+                env.iter()
+                    .find_map(|e| e.strip_prefix("GRPC_PORT="))
+                    .and_then(|p| p.parse::<u16>().ok())
+            })
+            .unwrap_or(50051);
+        // Bridge-networked apprentices are reached through their published port
+        let host_port = container_info
+            .as_ref()
+            .and_then(|info| Self::published_port(info, port))
+            .unwrap_or(port);
+
+        let mut client = None;
+        if container.state.as_deref() == Some("running") {
+            let endpoint = self.apprentice_endpoint(host_port)?;
+            client = Self::connect_with_retry(
+                endpoint,
+                self.auth_interceptor()?,
+                self.config.connect_attempts,
+                tokio::time::Duration::from_millis(self.config.connect_retry_delay_ms),
+            )
+            .await
+            .ok();
+        }
+
+        Ok(Apprentice {
+            _name: name.to_string(),
+            container_id,
+            _port: port,
+            image: container.image.clone().unwrap_or_default(),
+            client,
+        })
+    }
+
+    /// Find apprentice containers that are stopped, dead, or running but not
+    /// answering, by asking the container runtime directly.
+    pub async fn find_reapable(&self) -> Result<Vec<ReapCandidate>> {
+        use bollard::container::ListContainersOptions;
+
+        let mut filters = HashMap::new();
+        filters.insert("name".to_string(), vec!["apprentice-".to_string()]);
+        let containers = self
+            .docker
+            .list_containers(Some(ListContainersOptions {
+                all: true,
+                filters,
+                ..Default::default()
+            }))
+            .await?;
+
+        // Snapshot what we know, so the probes below don't hold the locks
+        let (ports, summoning) = {
+            let apprentices = self.apprentices.lock().await;
+            let summoning = self.summoning.lock().await;
+            let ports: HashMap<String, Option<u16>> = apprentices
+                .iter()
+                .map(|(name, apprentice)| {
+                    let unconnected_port = apprentice.client.is_none().then_some(apprentice._port);
+                    (name.clone(), unconnected_port)
+                })
+                .collect();
+            let summoning: Vec<String> = summoning.keys().cloned().collect();
+            (ports, summoning)
+        };
+
+        let mut candidates = Vec::new();
         for container in containers {
-            if let Some(names) = &container.names {
-                for name in names {
-                    if name.starts_with("/apprentice-") {
-                        let apprentice_name = name.strip_prefix("/apprentice-").unwrap();
-
-                        // Get port from container inspect (we'll need to inspect each container)
-                        let port = if let Ok(container_info) = self
-                            .docker
-                            .inspect_container(&container.id.clone().unwrap_or_default(), None)
-                            .await
-                        {
-                            if let Some(config) = container_info.config {
-                                if let Some(env) = config.env {
-                                    env.iter()
-                                        .find(|e| e.starts_with("GRPC_PORT="))
-                                        .and_then(|e| e.strip_prefix("GRPC_PORT="))
-                                        .and_then(|p| p.parse::<u16>().ok())
-                                        .unwrap_or(50051)
-                                } else {
-                                    50051
-                                }
-                            } else {
-                                50051
-                            }
-                        } else {
-                            50051
-                        };
-
-                        // Update next_port to avoid conflicts
-                        if port >= *next_port {
-                            *next_port = port + 1;
-                        }
-
-                        // Try to connect to the apprentice if it's running
-                        let mut client = None;
-                        if let Some(state) = &container.state {
-                            if state == "running" {
-                                let addr = format!("http://127.0.0.1:{port}");
-                                if let Ok(c) = ApprenticeClient::connect(addr).await {
-                                    client = Some(c);
-                                }
-                            }
-                        }
-
-                        apprentices.insert(
-                            apprentice_name.to_string(),
-                            Apprentice {
-                                _name: apprentice_name.to_string(),
-                                container_id: container.id.clone().unwrap_or_default(),
-                                _port: port,
-                                client,
-                            },
-                        );
-
-                        info!(
-                            "Discovered apprentice: {} (port: {})",
-                            apprentice_name, port
-                        );
+            let Some(name) = container
+                .names
+                .iter()
+                .flatten()
+                .find_map(|name| name.strip_prefix("/apprentice-"))
+            else {
+                continue;
+            };
+            if summoning.iter().any(|summoning| summoning == name) {
+                continue;
+            }
+            let container_id = container.id.clone().unwrap_or_default();
+            let state = container.state.clone().unwrap_or_default();
+            let reason = match state.as_str() {
+                "exited" | "dead" => state,
+                "running" => {
+                    // Not connected may only mean it was still booting when
+                    // we looked, so try it again before calling it unresponsive
+                    let Some(Some(port)) = ports.get(name) else {
+                        continue;
+                    };
+                    if self.probe(name, &container_id, *port).await {
+                        continue;
                     }
+                    "unresponsive".to_string()
+                }
+                // Created, restarting or paused containers may be mid-summon
+                // or mid-restart, so leave them alone
+                _ => continue,
+            };
+            candidates.push(ReapCandidate {
+                name: name.to_string(),
+                container_id,
+                reason,
+            });
+        }
+        candidates.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(candidates)
+    }
+
+    /// Connect to a running apprentice the sorcerer has no client for and ask
+    /// for its health, within `REAP_PROBE_TIMEOUT`. Keeps the client if it answers.
+    async fn probe(&self, name: &str, container_id: &str, port: u16) -> bool {
+        let probe = async {
+            let mut client = self.connect_to_container(container_id, port).await?;
+            client.health(tonic::Request::new(HealthRequest {})).await?;
+            Ok::<_, anyhow::Error>(client)
+        };
+        match tokio::time::timeout(REAP_PROBE_TIMEOUT, probe).await {
+            Ok(Ok(client)) => {
+                if let Some(apprentice) = self.apprentices.lock().await.get_mut(name) {
+                    apprentice.client = Some(client);
                 }
+                true
+            }
+            Ok(Err(e)) => {
+                info!("Apprentice {} did not answer: {}", name, e);
+                false
+            }
+            Err(_) => {
+                info!("Apprentice {} did not answer in time", name);
+                false
             }
         }
+    }
 
+    /// Force-remove a container found by `find_reapable`.
+    pub async fn reap(&self, candidate: &ReapCandidate) -> Result<()> {
+        self.docker
+            .remove_container(
+                &candidate.container_id,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        self.apprentices.lock().await.remove(&candidate.name);
+        info!(
+            "Reaped apprentice {} ({})",
+            candidate.name, candidate.reason
+        );
         Ok(())
     }
 
-    pub async fn summon_apprentice(&mut self, name: &str) -> Result<()> {
+    pub async fn summon_apprentice(&self, name: &str, options: &SummonOptions) -> Result<()> {
         // Validate apprentice name
         if !Self::is_valid_apprentice_name(name) {
-            return Err(anyhow!(
+            return Err(InvalidRequest(
                 "Invalid apprentice name. Names must be 1-32 characters, alphanumeric with hyphens/underscores only"
-            ));
+                    .to_string(),
+            )
+            .into());
         }
 
-        let mut apprentices = self.apprentices.lock().await;
+        // Reserve the name and a port up front, then release the locks so
+        // several apprentices can start concurrently
+        let port = self.reserve_summon(name).await?;
+        let result = self.create_apprentice(name, port, options).await;
+        record_outcome("sorcerer_summons_total", &result);
+        let (container_id, client) = match result {
+            Ok(created) => created,
+            Err(e) => {
+                self.summoning.lock().await.remove(name);
+                return Err(e);
+            }
+        };
 
-        // Check if apprentice already exists and is active (has a working client)
-        if let Some(existing_apprentice) = apprentices.get(name) {
-            if existing_apprentice.client.is_some() {
-                return Err(anyhow!("Apprentice {} already exists", name));
-            } else {
-                // Remove inactive apprentice entry and any existing container to allow recreation
-                apprentices.remove(name);
-                info!("Removed inactive apprentice {} to allow recreation", name);
-
-                // Try to remove any existing container with this name
-                let container_name = format!("apprentice-{name}");
-                if let Err(e) = self.docker.remove_container(&container_name, None).await {
-                    // Log but don't fail if container doesn't exist or can't be removed
-                    info!(
-                        "Could not remove existing container {}: {}",
-                        container_name, e
-                    );
+        let image = options
+            .image
+            .clone()
+            .unwrap_or_else(|| self.config.image_name.clone());
+        self.apprentices.lock().await.insert(
+            name.to_string(),
+            Apprentice {
+                _name: name.to_string(),
+                container_id,
+                _port: port,
+                image,
+                client: Some(client),
+            },
+        );
+        // Only release the reservation once the apprentice is registered, so
+        // the name is never free to claim in between
+        self.summoning.lock().await.remove(name);
+
+        info!("Apprentice {} summoned successfully", name);
+        Ok(())
+    }
+
+    /// Claim `name` for a summon in progress and pick the port it will use.
+    async fn reserve_summon(&self, name: &str) -> Result<u16> {
+        let (port, replaced) = {
+            let mut apprentices = self.apprentices.lock().await;
+            let mut summoning = self.summoning.lock().await;
+            if summoning.contains_key(name) {
+                return Err(anyhow!("Apprentice {} is already being summoned", name));
+            }
+
+            // Check if apprentice already exists and is active (has a working client)
+            let replaced = match apprentices.get(name) {
+                Some(existing) if existing.client.is_some() => {
+                    return Err(anyhow!("Apprentice {} already exists", name));
+                }
+                Some(_) => {
+                    // Remove inactive apprentice entry to allow recreation
+                    apprentices.remove(name);
+                    info!("Removed inactive apprentice {} to allow recreation", name);
+                    true
                 }
+                None => false,
+            };
+
+            let mut next_port = self.next_port.lock().await;
+            let port = self.select_port(name, &apprentices, &summoning, &[], &mut next_port)?;
+            summoning.insert(name.to_string(), port);
+            (port, replaced)
+        };
+
+        // The name is claimed, so the old container can be removed without
+        // holding up other summons. It may still be running, hence the force.
+        if replaced {
+            let container_name = format!("apprentice-{name}");
+            if let Err(e) = self
+                .docker
+                .remove_container(
+                    &container_name,
+                    Some(RemoveContainerOptions {
+                        force: true,
+                        ..Default::default()
+                    }),
+                )
+                .await
+            {
+                // Log but don't fail if container doesn't exist or can't be removed
+                info!(
+                    "Could not remove existing container {}: {}",
+                    container_name, e
+                );
             }
         }
 
-        let port = {
-            let mut next_port = self.next_port.lock().await;
+        Ok(port)
+    }
+
+    /// Pick the port a new apprentice `name` gets, advancing `next_port` when
+    /// ports are sequential. `planned` holds ports already promised in the
+    /// same batch. An inactive apprentice of the same name is replaced by the
+    /// summon, so its port counts as free.
+    fn select_port(
+        &self,
+        name: &str,
+        apprentices: &HashMap<String, Apprentice>,
+        summoning: &HashMap<String, u16>,
+        planned: &[u16],
+        next_port: &mut u16,
+    ) -> Result<u16> {
+        if !self.config.hash_ports {
             let port = *next_port;
             *next_port += 1;
-            port
-        };
+            return Ok(port);
+        }
+
+        let used_ports: Vec<u16> = apprentices
+            .iter()
+            .filter(|(other, _)| other.as_str() != name)
+            .map(|(_, a)| a._port)
+            .chain(summoning.values().copied())
+            .chain(planned.iter().copied())
+            .collect();
+        Self::hashed_port(name, self.config.starting_port, &used_ports)
+    }
+
+    /// Work out the container, port and image each apprentice would get,
+    /// without reserving anything or touching containers.
+    pub async fn plan_summon(
+        &self,
+        names: &[String],
+        options: &SummonOptions,
+    ) -> Result<Vec<SummonPlan>> {
+        let apprentices = self.apprentices.lock().await;
+        let summoning = self.summoning.lock().await;
+        let mut planned_ports = Vec::new();
+        let mut next_port = *self.next_port.lock().await;
+
+        let mut plans = Vec::new();
+        for name in names {
+            if !Self::is_valid_apprentice_name(name) {
+                return Err(InvalidRequest(format!("Invalid apprentice name: {}", name)).into());
+            }
+            if summoning.contains_key(name) || plans.iter().any(|p: &SummonPlan| &p.name == name) {
+                return Err(anyhow!("Apprentice {} is already being summoned", name));
+            }
+            if apprentices.get(name).is_some_and(|a| a.client.is_some()) {
+                return Err(anyhow!("Apprentice {} already exists", name));
+            }
+
+            let port = self.select_port(
+                name,
+                &apprentices,
+                &summoning,
+                &planned_ports,
+                &mut next_port,
+            )?;
+            planned_ports.push(port);
+
+            let image = options
+                .image
+                .clone()
+                .unwrap_or_else(|| self.config.image_name.clone());
+            self.check_image_pinned(&image)?;
+            plans.push(SummonPlan {
+                name: name.clone(),
+                container_name: format!("apprentice-{name}"),
+                port,
+                image,
+            });
+        }
 
+        Ok(plans)
+    }
+
+    /// Create, start and connect to the container for a reserved apprentice.
+    async fn create_apprentice(
+        &self,
+        name: &str,
+        port: u16,
+        options: &SummonOptions,
+    ) -> Result<(String, ApprenticeConnection)> {
         info!("Summoning apprentice {} on port {}", name, port);
+        let image = options.image.as_deref().unwrap_or(&self.config.image_name);
+        self.check_image_pinned(image)?;
+        if self.config.auto_pull
+            && pinned_digest(image).is_some()
+            && self.docker.inspect_image(image).await.is_err()
+        {
+            // Pulling by digest makes the registry hand over exactly that
+            // content, or fail
+            info!("Pulling pinned image {}", image);
+            self.pull_image(image).await?;
+        }
 
         // Get API key from environment
         let api_key = std::env::var("ANTHROPIC_API_KEY")?;
 
+        let mut env = vec![
+            format!("APPRENTICE_NAME={}", name),
+            format!("GRPC_PORT={}", port),
+            format!("ANTHROPIC_API_KEY={}", api_key),
+        ];
+        if let Some(token) = &self.config.auth_token {
+            env.push(format!("APPRENTICE_TOKEN={token}"));
+        }
+        if let Some(model) = &options.model {
+            env.push(format!("CLAUDE_MODEL={model}"));
+        }
+        if let Some(extra) = &options.prompt_append {
+            env.push(format!("SYSTEM_PROMPT_APPEND={extra}"));
+        }
+        if let Ok(base_url) = std::env::var("ANTHROPIC_BASE_URL") {
+            env.push(format!("ANTHROPIC_BASE_URL={base_url}"));
+        }
+        for key in [
+            "MAX_RESPONSE_CHARS",
+            "AGENT_COMPACT_HISTORY",
+            "AGENT_COMPACT_THRESHOLD",
+            "AGENT_COMPACT_KEEP",
+            "CLAUDE_LOG_BODIES",
+            "MAX_REPLAY_TURNS",
+        ] {
+            if let Ok(value) = std::env::var(key) {
+                env.push(format!("{key}={value}"));
+            }
+        }
+        if let Ok(limit) = std::env::var("SPELL_RATE_LIMIT") {
+            env.push(format!("SPELL_RATE_LIMIT={limit}"));
+        }
+        if let Ok(log_format) = std::env::var("LOG_FORMAT") {
+            env.push(format!("LOG_FORMAT={log_format}"));
+        }
+
+        env.extend(options.env.iter().cloned());
+
+        // Mount the server certificate into the container when TLS is enabled
+        let mut binds = Vec::new();
+        if let Some((cert, key)) = self.config.tls_files() {
+            binds.push(format!("{cert}:{TLS_CERT_MOUNT}:ro"));
+            binds.push(format!("{key}:{TLS_KEY_MOUNT}:ro"));
+            env.push(format!("APPRENTICE_TLS_CERT={TLS_CERT_MOUNT}"));
+            env.push(format!("APPRENTICE_TLS_KEY={TLS_KEY_MOUNT}"));
+        }
+        binds.extend(options.mounts.iter().cloned());
+
+        use bollard::models::{RestartPolicy, RestartPolicyNameEnum};
+        let restart_policy = match self.config.restart_policy.as_str() {
+            "no" => RestartPolicyNameEnum::NO,
+            "on-failure" => RestartPolicyNameEnum::ON_FAILURE,
+            "unless-stopped" => RestartPolicyNameEnum::UNLESS_STOPPED,
+            other => {
+                return Err(anyhow!(
+                    "Unknown SORCERER_RESTART_POLICY {}; expected no, on-failure or unless-stopped",
+                    other
+                ))
+            }
+        };
+
         // Create container
         let config = Config {
-            image: Some(self.config.image_name.clone()),
-            env: Some(vec![
-                format!("APPRENTICE_NAME={}", name),
-                format!("GRPC_PORT={}", port),
-                format!("ANTHROPIC_API_KEY={}", api_key),
-            ]),
-            exposed_ports: Some(HashMap::from([("50051/tcp".to_string(), HashMap::new())])),
+            image: Some(image.to_string()),
+            env: Some(env),
+            exposed_ports: Some(HashMap::from([(format!("{port}/tcp"), HashMap::new())])),
             host_config: Some(bollard::models::HostConfig {
-                network_mode: Some("host".to_string()),
+                network_mode: Some(self.config.network_mode.clone()),
+                // Without host networking, publish the gRPC port on an ephemeral local port
+                port_bindings: (self.config.network_mode != "host").then(|| {
+                    HashMap::from([(
+                        format!("{port}/tcp"),
+                        Some(vec![bollard::models::PortBinding {
+                            host_ip: Some("127.0.0.1".to_string()),
+                            host_port: Some(String::new()),
+                        }]),
+                    )])
+                }),
+                binds: (!binds.is_empty()).then_some(binds),
+                memory: options.memory,
+                nano_cpus: options.nano_cpus,
+                restart_policy: Some(RestartPolicy {
+                    name: Some(restart_policy),
+                    maximum_retry_count: None,
+                }),
                 ..Default::default()
             }),
             ..Default::default()
         };
 
-        let container = self
+        let create_options = CreateContainerOptions {
+            name: format!("apprentice-{name}"),
+            ..Default::default()
+        };
+        let container = match self
             .docker
-            .create_container(
-                Some(CreateContainerOptions {
-                    name: format!("apprentice-{name}"),
-                    ..Default::default()
-                }),
-                config,
-            )
-            .await?;
+            .create_container(Some(create_options.clone()), config.clone())
+            .await
+        {
+            Ok(container) => container,
+            Err(e) if Self::is_missing_image(&e) => {
+                if !self.config.auto_pull {
+                    return Err(anyhow!(
+                        "Container image {} not found.\n  \
+                         Build it with: podman build -f apprentice/Containerfile -t {} .\n  \
+                         (or set SORCERER_AUTO_PULL=1 to pull it from a registry)",
+                        image,
+                        image
+                    ));
+                }
+
+                info!("Image {} not found locally, pulling it", image);
+                self.pull_image(image).await?;
+                self.docker
+                    .create_container(Some(create_options), config)
+                    .await?
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let connected = match self
+            .start_and_connect(&container.id, port, options.health_wait)
+            .await
+        {
+            Ok(client) => Self::verify_identity(client, name, port).await,
+            Err(e) => Err(e),
+        };
+        match connected {
+            Ok(client) => Ok((container.id, client)),
+            Err(e) => {
+                // Surface the apprentice's own output, then clean up the failed
+                // container so a retry under the same name starts fresh
+                let logs = self
+                    .container_logs(&container.id, 20)
+                    .await
+                    .unwrap_or_default();
+                let mut details = String::new();
+                if !logs.is_empty() {
+                    details.push_str(&format!("\n  Last container logs:\n{}", logs));
+                }
+                if options.keep_on_failure {
+                    details.push_str(&format!(
+                        "\n  Container apprentice-{} was kept for inspection; \
+                         remove it with: srcrr reap",
+                        name
+                    ));
+                } else if let Err(rm_err) = self
+                    .docker
+                    .remove_container(
+                        &container.id,
+                        Some(RemoveContainerOptions {
+                            force: true,
+                            ..Default::default()
+                        }),
+                    )
+                    .await
+                {
+                    warn!("Failed to remove failed container: {}", rm_err);
+                }
+
+                if details.is_empty() {
+                    return Err(e);
+                }
+                // Keep the original error in the chain so a deadline stays detectable
+                let message = format!("{}{}", e, details);
+                Err(e.context(message))
+            }
+        }
+    }
+
+    /// Make sure the apprentice on the other end of `client` is the one we just
+    /// summoned. With host networking, a port collision can silently connect
+    /// us to a different apprentice.
+    async fn verify_identity(
+        mut client: ApprenticeConnection,
+        name: &str,
+        port: u16,
+    ) -> Result<ApprenticeConnection> {
+        match client
+            .identity(tonic::Request::new(IdentityRequest {}))
+            .await
+        {
+            Ok(response) => {
+                let reported = response.into_inner().name;
+                if reported != name {
+                    return Err(anyhow!(
+                        "Port {} is answered by apprentice {} instead of {}; \
+                         another apprentice may already be using it",
+                        port,
+                        reported,
+                        name
+                    ));
+                }
+            }
+            // Images built before the Identity RPC can't be checked
+            Err(status) if status.code() == tonic::Code::Unimplemented => {
+                warn!(
+                    "Apprentice {} can't report its identity; skipping the check",
+                    name
+                );
+            }
+            Err(status) => return Err(status.into()),
+        }
+        Ok(client)
+    }
+
+    /// Derive a port from the apprentice's name so it is stable across
+    /// restarts, probing upwards past ports other apprentices already hold.
+    fn hashed_port(name: &str, starting_port: u16, used_ports: &[u16]) -> Result<u16> {
+        // FNV-1a, which unlike std's hasher is stable across Rust releases
+        let hash = name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        let offset = (hash % HASHED_PORT_RANGE as u64) as u16;
+
+        (0..HASHED_PORT_RANGE)
+            .filter_map(|probe| starting_port.checked_add((offset + probe) % HASHED_PORT_RANGE))
+            .find(|port| !used_ports.contains(port))
+            .ok_or_else(|| anyhow!("No free port left in the hashed port range"))
+    }
+
+    /// Refuse images that aren't pinned by digest when SORCERER_REQUIRE_PINNED is set.
+    fn check_image_pinned(&self, image: &str) -> Result<()> {
+        if !self.config.require_pinned || pinned_digest(image).is_some() {
+            return Ok(());
+        }
+        warn!("Refusing unpinned image {}", image);
+        Err(anyhow!(
+            "Image {} is not pinned by digest, and SORCERER_REQUIRE_PINNED is set.\n  \
+             Use a reference like <image>@sha256:<digest> instead of a tag such as latest",
+            image
+        ))
+    }
+
+    /// The registry digest of a local image: taken from the reference itself
+    /// when pinned, otherwise from the runtime's record of where it was pulled.
+    /// Locally built images have none.
+    async fn image_digest(&self, image: &str) -> Option<String> {
+        if let Some(digest) = pinned_digest(image) {
+            return Some(digest.to_string());
+        }
+        self.docker
+            .inspect_image(image)
+            .await
+            .ok()?
+            .repo_digests?
+            .iter()
+            .find_map(|reference| pinned_digest(reference).map(str::to_string))
+    }
+
+    fn is_missing_image(error: &bollard::errors::Error) -> bool {
+        matches!(
+            error,
+            bollard::errors::Error::DockerResponseServerError {
+                status_code: 404,
+                message,
+            } if message.to_lowercase().contains("image")
+        )
+    }
+
+    async fn pull_image(&self, image: &str) -> Result<()> {
+        use bollard::image::CreateImageOptions;
+        use futures_util::StreamExt;
 
+        let mut stream = self.docker.create_image(
+            Some(CreateImageOptions {
+                from_image: image,
+                ..Default::default()
+            }),
+            None,
+            None,
+        );
+        while let Some(progress) = stream.next().await {
+            progress.map_err(|e| anyhow!("Failed to pull image {}: {}", image, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// The host port the runtime published for the container's gRPC `port`, if any.
+    fn published_port(info: &bollard::models::ContainerInspectResponse, port: u16) -> Option<u16> {
+        info.network_settings
+            .as_ref()?
+            .ports
+            .as_ref()?
+            .get(&format!("{port}/tcp"))?
+            .as_ref()?
+            .iter()
+            .find_map(|binding| binding.host_port.as_deref()?.parse().ok())
+    }
+
+    async fn start_and_connect(
+        &self,
+        container_id: &str,
+        port: u16,
+        health_wait: Option<HealthWait>,
+    ) -> Result<ApprenticeConnection> {
         self.docker
-            .start_container(&container.id, None::<StartContainerOptions<String>>)
+            .start_container(container_id, None::<StartContainerOptions<String>>)
             .await?;
 
+        if let Some(wait) = health_wait {
+            return self.poll_until_healthy(container_id, port, wait).await;
+        }
+
         // Wait for container to be ready
         tokio::time::sleep(tokio::time::Duration::from_secs(
             self.config.container_ready_timeout,
         ))
         .await;
 
-        // Connect to apprentice (using localhost since we're using host networking)
-        let addr = format!("http://127.0.0.1:{port}");
-        let client = ApprenticeClient::connect(addr.clone()).await?;
+        let mut client = self.connect_to_container(container_id, port).await?;
+        self.wait_until_healthy(&mut client).await?;
+        Ok(client)
+    }
 
-        apprentices.insert(
-            name.to_string(),
-            Apprentice {
-                _name: name.to_string(),
-                container_id: container.id,
-                _port: port,
-                client: Some(client),
-            },
-        );
+    /// Repeatedly connect and health-check a new apprentice every
+    /// `wait.interval` until it is ready or `wait.deadline` passes.
+    async fn poll_until_healthy(
+        &self,
+        container_id: &str,
+        port: u16,
+        wait: HealthWait,
+    ) -> Result<ApprenticeConnection> {
+        let deadline = tokio::time::Instant::now() + wait.deadline;
+        let mut last_error = "no attempt finished".to_string();
+        loop {
+            let attempt = async {
+                let endpoint = self.container_endpoint(container_id, port).await?;
+                let channel = endpoint.connect().await?;
+                let mut client =
+                    ApprenticeClient::with_interceptor(channel, self.auth_interceptor()?);
+                let health = client.health(HealthRequest {}).await?.into_inner();
+                if !health.healthy {
+                    return Err(anyhow!("{}", health.reason));
+                }
+                Ok(client)
+            };
+            match tokio::time::timeout_at(deadline, attempt).await {
+                Ok(Ok(client)) => return Ok(client),
+                Ok(Err(e)) => last_error = e.to_string(),
+                Err(_) => {}
+            }
 
-        info!("Apprentice {} summoned successfully", name);
-        Ok(())
+            if tokio::time::Instant::now() + wait.interval >= deadline {
+                return Err(HealthDeadlineElapsed {
+                    deadline: wait.deadline,
+                    last_error,
+                }
+                .into());
+            }
+            tokio::time::sleep(wait.interval).await;
+        }
     }
 
-    pub async fn cast_spell(&mut self, name: &str, incantation: &str) -> Result<String> {
-        let mut apprentices = self.apprentices.lock().await;
-        let apprentice = apprentices
-            .get_mut(name)
-            .ok_or_else(|| anyhow!("Apprentice {} not found", name))?;
+    async fn connect_to_container(
+        &self,
+        container_id: &str,
+        port: u16,
+    ) -> Result<ApprenticeConnection> {
+        let endpoint = self.container_endpoint(container_id, port).await?;
+        Self::connect_with_retry(
+            endpoint,
+            self.auth_interceptor()?,
+            self.config.connect_attempts,
+            tokio::time::Duration::from_millis(self.config.connect_retry_delay_ms),
+        )
+        .await
+    }
+
+    async fn container_endpoint(&self, container_id: &str, port: u16) -> Result<Endpoint> {
+        // With host networking the apprentice listens on localhost directly,
+        // otherwise connect through the port the runtime published for it
+        let host_port = if self.config.network_mode == "host" {
+            port
+        } else {
+            let info = self.docker.inspect_container(container_id, None).await?;
+            Self::published_port(&info, port)
+                .ok_or_else(|| anyhow!("Container did not publish gRPC port {}", port))?
+        };
+        self.apprentice_endpoint(host_port)
+    }
 
-        let client = apprentice
+    /// Try once more to attach to an apprentice whose container exists but
+    /// couldn't be reached earlier, e.g. because it was still booting.
+    async fn reconnect(&self, name: &str) -> Result<ApprenticeConnection> {
+        let (container_id, port) = {
+            let apprentices = self.apprentices.lock().await;
+            let apprentice = apprentices.get(name).ok_or_else(|| not_found(name))?;
+            (apprentice.container_id.clone(), apprentice._port)
+        };
+
+        info!("Reconnecting to apprentice {}", name);
+        let client = self.connect_to_container(&container_id, port).await?;
+        if let Some(apprentice) = self.apprentices.lock().await.get_mut(name) {
+            apprentice.client = Some(client.clone());
+        }
+
+        Ok(client)
+    }
+
+    /// Clone an apprentice's client out of the map, so the lock isn't held
+    /// while the RPC runs and one slow apprentice can't stall the others.
+    async fn connected_client(&self, name: &str) -> Result<ApprenticeConnection> {
+        let apprentices = self.apprentices.lock().await;
+        apprentices
+            .get(name)
+            .ok_or_else(|| not_found(name))?
             .client
-            .as_mut()
-            .ok_or_else(|| anyhow!("Apprentice {} is not connected", name))?;
+            .clone()
+            .ok_or_else(|| anyhow!("Apprentice {} is not connected", name))
+    }
+
+    /// Ask an apprentice whether it can take spells right now.
+    pub async fn check_health(&self, name: &str) -> Result<()> {
+        let mut client = self.connected_client(name).await?;
+
+        let response = client
+            .health(tonic::Request::new(HealthRequest {}))
+            .await?
+            .into_inner();
+
+        if response.healthy {
+            Ok(())
+        } else {
+            Err(anyhow!("{}", response.reason))
+        }
+    }
+
+    /// Poll the apprentice's health check until it reports it can take spells.
+    async fn wait_until_healthy(&self, client: &mut ApprenticeConnection) -> Result<()> {
+        let mut delay = tokio::time::Duration::from_millis(self.config.connect_retry_delay_ms);
+        let mut reason = String::new();
+        for attempt in 1..=self.config.connect_attempts {
+            match client.health(HealthRequest {}).await {
+                Ok(response) if response.get_ref().healthy => return Ok(()),
+                Ok(response) => reason = response.into_inner().reason,
+                Err(status) => reason = status.message().to_string(),
+            }
+            info!(
+                "Apprentice not ready yet (attempt {}/{}): {}",
+                attempt, self.config.connect_attempts, reason
+            );
+            if attempt < self.config.connect_attempts {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+        }
+
+        Err(anyhow!("Apprentice never became ready: {}", reason))
+    }
+
+    /// Stream an apprentice's raw container output, starting with the last
+    /// `tail` lines (optionally only those after the unix time `since`).
+    pub async fn container_log_stream(
+        &self,
+        name: &str,
+        tail: usize,
+        since: i64,
+        follow: bool,
+    ) -> Result<impl futures_util::Stream<Item = Result<String>>> {
+        use bollard::container::LogsOptions;
+        use futures_util::StreamExt;
+
+        let container_id = {
+            let apprentices = self.apprentices.lock().await;
+            apprentices
+                .get(name)
+                .ok_or_else(|| not_found(name))?
+                .container_id
+                .clone()
+        };
+
+        Ok(self
+            .docker
+            .logs(
+                &container_id,
+                Some(LogsOptions::<String> {
+                    stdout: true,
+                    stderr: true,
+                    follow,
+                    since,
+                    tail: tail.to_string(),
+                    ..Default::default()
+                }),
+            )
+            .map(|chunk| Ok(chunk?.to_string())))
+    }
+
+    /// Fetch the last `tail` lines of a container's stdout and stderr.
+    async fn container_logs(&self, container_id: &str, tail: usize) -> Result<String> {
+        use bollard::container::LogsOptions;
+        use futures_util::StreamExt;
+
+        let mut stream = self.docker.logs(
+            container_id,
+            Some(LogsOptions::<String> {
+                stdout: true,
+                stderr: true,
+                tail: tail.to_string(),
+                ..Default::default()
+            }),
+        );
+
+        let mut logs = String::new();
+        while let Some(chunk) = stream.next().await {
+            for line in chunk?.to_string().lines() {
+                logs.push_str("    ");
+                logs.push_str(line);
+                logs.push('\n');
+            }
+        }
+
+        Ok(logs)
+    }
+
+    pub async fn cast_spell(
+        &self,
+        name: &str,
+        incantation: &str,
+        options: &SpellOptions,
+    ) -> Result<String> {
+        let outcome = self.cast_spell_detailed(name, incantation, options).await?;
+        if outcome.success {
+            Ok(outcome.response)
+        } else {
+            Err(anyhow!("Tell failed: {}", outcome.error))
+        }
+    }
+
+    /// Cast a spell and report its id, token usage and latency. A spell the
+    /// apprentice rejected or failed comes back as `success: false` rather than
+    /// an error; errors are reserved for not reaching the apprentice at all.
+    pub async fn cast_spell_detailed(
+        &self,
+        name: &str,
+        incantation: &str,
+        options: &SpellOptions,
+    ) -> Result<SpellOutcome> {
+        if !options.allow_empty {
+            Self::check_incantation(name, incantation)?;
+        }
+
+        // Clone the client out so spells to different apprentices can run concurrently
+        let client = {
+            let apprentices = self.apprentices.lock().await;
+            let apprentice = apprentices.get(name).ok_or_else(|| not_found(name))?;
+
+            apprentice.client.clone()
+        };
+        let mut client = match client {
+            Some(client) => client,
+            None => self.reconnect(name).await.map_err(|e| {
+                anyhow!(
+                    "Apprentice {} exists but is not connected, and reconnecting failed: {}\n  \
+                     Check its output with: srcrr logs {}\n  \
+                     Or recreate it with: srcrr kill {} && srcrr summon {}",
+                    name,
+                    e,
+                    name,
+                    name,
+                    name
+                )
+            })?,
+        };
 
         let request = tonic::Request::new(SpellRequest {
             incantation: incantation.to_string(),
             spell_id: uuid::Uuid::new_v4().to_string(),
+            model: options.model.clone(),
+            session_id: options.session.clone().unwrap_or_default(),
         });
 
-        let response = client.cast_spell(request).await?;
-        let spell_response = response.into_inner();
+        let started = std::time::Instant::now();
+        let response = client.cast_spell(request).await;
+        let latency = started.elapsed();
+        metrics::histogram!("sorcerer_spell_duration_seconds").record(latency.as_secs_f64());
+        let spell_response = match response {
+            Ok(response) => response.into_inner(),
+            Err(e) => {
+                metrics::counter!("sorcerer_spells_total", "result" => "error").increment(1);
+                return Err(e.into());
+            }
+        };
+        metrics::counter!("sorcerer_tokens_total", "kind" => "input")
+            .increment(spell_response.input_tokens.into());
+        metrics::counter!("sorcerer_tokens_total", "kind" => "output")
+            .increment(spell_response.output_tokens.into());
 
-        if spell_response.success {
-            Ok(spell_response.result)
+        let result = if spell_response.success {
+            "success"
         } else {
-            Err(anyhow!("Tell failed: {}", spell_response.error))
-        }
+            "error"
+        };
+        metrics::counter!("sorcerer_spells_total", "result" => result).increment(1);
+
+        Ok(SpellOutcome {
+            spell_id: spell_response.spell_id,
+            success: spell_response.success,
+            response: spell_response.result,
+            error: spell_response.error,
+            input_tokens: spell_response.input_tokens,
+            output_tokens: spell_response.output_tokens,
+            model: spell_response.model,
+            truncated: spell_response.truncated,
+            latency,
+        })
     }
 
     pub async fn list_apprentices(&self) -> Result<Vec<String>> {
@@ -322,31 +1486,112 @@ impl Sorcerer {
             .collect())
     }
 
-    pub async fn kill_apprentice(&mut self, name: &str) -> Result<()> {
-        let mut apprentices = self.apprentices.lock().await;
-        let apprentice = apprentices
+    /// Look up the apprentices `kill --dry-run` would remove, without contacting
+    /// them. The state is only "connected" or "disconnected".
+    pub async fn plan_kill(&self, names: &[String]) -> Result<Vec<ApprenticeInfo>> {
+        let apprentices = self.apprentices.lock().await;
+        names
+            .iter()
+            .map(|name| {
+                let apprentice = apprentices.get(name).ok_or_else(|| not_found(name))?;
+                Ok(ApprenticeInfo {
+                    name: name.clone(),
+                    state: if apprentice.client.is_some() {
+                        "connected"
+                    } else {
+                        "disconnected"
+                    }
+                    .to_string(),
+                    port: apprentice._port,
+                    container_id: apprentice.container_id.clone(),
+                    image: apprentice.image.clone(),
+                    image_digest: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Every known apprentice, connected or not, with its state, port and container.
+    pub async fn list_apprentices_detailed(&self) -> Result<Vec<ApprenticeInfo>> {
+        let mut infos: Vec<ApprenticeInfo> = {
+            let apprentices = self.apprentices.lock().await;
+            apprentices
+                .iter()
+                .map(|(name, apprentice)| ApprenticeInfo {
+                    name: name.clone(),
+                    state: "disconnected".to_string(),
+                    port: apprentice._port,
+                    container_id: apprentice.container_id.clone(),
+                    image: apprentice.image.clone(),
+                    image_digest: None,
+                })
+                .collect()
+        };
+
+        // Resolved outside the lock, once per distinct image
+        let mut digests: HashMap<String, Option<String>> = HashMap::new();
+        for info in &mut infos {
+            if !digests.contains_key(&info.image) {
+                let digest = self.image_digest(&info.image).await;
+                digests.insert(info.image.clone(), digest);
+            }
+            info.image_digest = digests[&info.image].clone();
+        }
+
+        let statuses = self.get_all_status().await?;
+        for info in &mut infos {
+            if let Some(status) = statuses.get(&info.name) {
+                info.state = if status.draining {
+                    "draining".to_string()
+                } else {
+                    status.state.clone()
+                };
+            }
+        }
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(infos)
+    }
+
+    /// Remove an apprentice and its container. Unless `force` is set, the
+    /// apprentice is first asked to shut down and its container is stopped; both
+    /// steps are bounded by a timeout so a wedged container can't block the kill.
+    pub async fn kill_apprentice(&self, name: &str, force: bool) -> Result<()> {
+        let apprentice = self
+            .apprentices
+            .lock()
+            .await
             .remove(name)
-            .ok_or_else(|| anyhow!("Apprentice {} not found", name))?;
+            .ok_or_else(|| not_found(name))?;
 
-        // Try to gracefully shut down via gRPC first
-        if let Some(mut client) = apprentice.client {
-            let _ = client
-                .kill(tonic::Request::new(spells::KillRequest {
+        if !force {
+            // Try to gracefully shut down via gRPC first
+            if let Some(mut client) = apprentice.client {
+                let request = client.kill(tonic::Request::new(spells::KillRequest {
                     reason: "Sorcerer's command".to_string(),
-                }))
-                .await;
-        }
+                }));
+                if tokio::time::timeout(KILL_STEP_TIMEOUT, request)
+                    .await
+                    .is_err()
+                {
+                    warn!("Apprentice {} did not acknowledge the kill in time", name);
+                }
+            }
 
-        // Stop and remove container
-        if let Err(e) = self
-            .docker
-            .stop_container(&apprentice.container_id, None)
+            match tokio::time::timeout(
+                KILL_STEP_TIMEOUT,
+                self.docker.stop_container(&apprentice.container_id, None),
+            )
             .await
-        {
-            warn!("Failed to stop container gracefully: {}", e);
+            {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => warn!("Failed to stop container gracefully: {}", e),
+                Err(_) => warn!("Timed out stopping container for {}, removing it", name),
+            }
         }
 
-        self.docker
+        let removed = self
+            .docker
             .remove_container(
                 &apprentice.container_id,
                 Some(RemoveContainerOptions {
@@ -354,28 +1599,47 @@ impl Sorcerer {
                     ..Default::default()
                 }),
             )
-            .await?;
+            .await;
+        record_outcome("sorcerer_kills_total", &removed);
+        removed?;
 
         info!("Apprentice {} has been killed", name);
         Ok(())
     }
 
-    pub async fn get_all_status(&mut self) -> Result<HashMap<String, spells::StatusResponse>> {
-        let mut results = HashMap::new();
-        let mut apprentices = self.apprentices.lock().await;
+    pub async fn get_all_status(&self) -> Result<HashMap<String, spells::StatusResponse>> {
+        // Clone the clients out so a slow apprentice doesn't hold the lock
+        let clients: Vec<(String, ApprenticeConnection)> = {
+            let apprentices = self.apprentices.lock().await;
+            apprentices
+                .iter()
+                .filter_map(|(name, apprentice)| {
+                    apprentice
+                        .client
+                        .clone()
+                        .map(|client| (name.clone(), client))
+                })
+                .collect()
+        };
 
-        for (name, apprentice) in apprentices.iter_mut() {
-            if let Some(client) = &mut apprentice.client {
-                match client
+        let responses = futures_util::future::join_all(clients.into_iter().map(
+            |(name, mut client)| async move {
+                let response = client
                     .get_status(tonic::Request::new(StatusRequest {}))
-                    .await
-                {
-                    Ok(response) => {
-                        results.insert(name.clone(), response.into_inner());
-                    }
-                    Err(e) => {
-                        warn!("Failed to get status for {}: {}", name, e);
-                    }
+                    .await;
+                (name, response)
+            },
+        ))
+        .await;
+
+        let mut results = HashMap::new();
+        for (name, response) in responses {
+            match response {
+                Ok(response) => {
+                    results.insert(name, response.into_inner());
+                }
+                Err(e) => {
+                    warn!("Failed to get status for {}: {}", name, e);
                 }
             }
         }
@@ -383,24 +1647,212 @@ impl Sorcerer {
         Ok(results)
     }
 
-    pub async fn get_chat_history(&mut self, name: &str, lines: usize) -> Result<Vec<String>> {
-        let mut apprentices = self.apprentices.lock().await;
-        let apprentice = apprentices
-            .get_mut(name)
-            .ok_or_else(|| anyhow!("Apprentice {} not found", name))?;
+    pub async fn get_chat_history(
+        &self,
+        name: &str,
+        lines: usize,
+        session: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let entries = self.get_chat_entries(name, lines, session).await?;
+        Ok(entries
+            .iter()
+            .map(|entry| {
+                let speaker = if entry.role == "user" {
+                    "Sorcerer"
+                } else {
+                    name
+                };
+                format!("{speaker}: {}", entry.content)
+            })
+            .collect())
+    }
 
-        let client = apprentice
-            .client
-            .as_mut()
-            .ok_or_else(|| anyhow!("Apprentice {} is not connected", name))?;
+    /// Like `get_chat_history`, but returns each turn's role, text and
+    /// timestamp unformatted, for callers that render history themselves.
+    pub async fn get_chat_entries(
+        &self,
+        name: &str,
+        lines: usize,
+        session: Option<&str>,
+    ) -> Result<Vec<spells::HistoryTurn>> {
+        let mut client = self.connected_client(name).await?;
 
         let request = tonic::Request::new(ChatHistoryRequest {
             lines: lines as i32,
+            session_id: session.unwrap_or_default().to_string(),
         });
 
         let response = client.get_chat_history(request).await?;
-        let chat_response = response.into_inner();
+        Ok(response.into_inner().entries)
+    }
+
+    /// Add a prior conversation to an apprentice's default session, so later
+    /// messages have it as context.
+    pub async fn append_history(
+        &self,
+        name: &str,
+        transcript: &crate::transcript::Transcript,
+    ) -> Result<()> {
+        let mut client = self.connected_client(name).await?;
+
+        let request = tonic::Request::new(AppendHistoryRequest {
+            session_id: String::new(),
+            turns: transcript
+                .turns
+                .iter()
+                .map(|turn| spells::HistoryTurn {
+                    role: turn.role.clone(),
+                    content: turn.content.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+        });
+        client.append_history(request).await?;
+        Ok(())
+    }
+
+    /// The container image an apprentice is running.
+    pub async fn get_image(&self, name: &str) -> Result<String> {
+        let apprentices = self.apprentices.lock().await;
+        apprentices
+            .get(name)
+            .map(|apprentice| apprentice.image.clone())
+            .ok_or_else(|| not_found(name))
+    }
+
+    /// The container runtime's full view of an apprentice's container, for
+    /// debugging when the apprentice itself can't be reached.
+    pub async fn inspect_container(
+        &self,
+        name: &str,
+    ) -> Result<bollard::models::ContainerInspectResponse> {
+        let container_id = {
+            let apprentices = self.apprentices.lock().await;
+            apprentices
+                .get(name)
+                .map(|apprentice| apprentice.container_id.clone())
+                .ok_or_else(|| not_found(name))?
+        };
+        Ok(self.docker.inspect_container(&container_id, None).await?)
+    }
+
+    pub async fn get_config(&self, name: &str) -> Result<spells::ConfigResponse> {
+        let mut client = self.connected_client(name).await?;
+
+        let response = client
+            .get_config(tonic::Request::new(ConfigRequest {}))
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    pub async fn list_sessions(&self, name: &str) -> Result<Vec<String>> {
+        let mut client = self.connected_client(name).await?;
+
+        let response = client
+            .list_sessions(tonic::Request::new(ListSessionsRequest {}))
+            .await?;
+
+        Ok(response.into_inner().sessions)
+    }
+
+    pub async fn get_system_prompt(&self, name: &str) -> Result<Option<String>> {
+        let mut client = self.connected_client(name).await?;
+
+        let response = client
+            .get_system_prompt(tonic::Request::new(GetSystemPromptRequest {}))
+            .await?;
+
+        Ok(response.into_inner().prompt)
+    }
+
+    /// Replace an apprentice's system prompt; an empty prompt clears it.
+    pub async fn set_system_prompt(&self, name: &str, prompt: &str) -> Result<()> {
+        let mut client = self.connected_client(name).await?;
+
+        client
+            .set_system_prompt(tonic::Request::new(SetSystemPromptRequest {
+                prompt: prompt.to_string(),
+            }))
+            .await?;
+
+        Ok(())
+    }
+
+    /// The last message sent to an apprentice in `session`, whether or not the
+    /// spell succeeded.
+    pub async fn get_last_incantation(&self, name: &str, session: Option<&str>) -> Result<String> {
+        let mut client = self.connected_client(name).await?;
+
+        let response = client
+            .get_last_incantation(tonic::Request::new(LastIncantationRequest {
+                session_id: session.unwrap_or_default().to_string(),
+            }))
+            .await?;
+
+        response
+            .into_inner()
+            .incantation
+            .ok_or_else(|| anyhow!("Apprentice {} has not been told anything yet", name))
+    }
+
+    /// Stop an apprentice accepting new spells; the ones it is casting still
+    /// finish. Returns how many spells are still in flight.
+    pub async fn drain(&self, name: &str) -> Result<u32> {
+        let mut client = self.connected_client(name).await?;
+
+        let response = client.drain(tonic::Request::new(DrainRequest {})).await?;
+        Ok(response.into_inner().in_flight)
+    }
+
+    /// Drain an apprentice, then wait for all its spells in flight to finish.
+    pub async fn drain_and_wait(&self, name: &str) -> Result<()> {
+        if self.drain(name).await? == 0 {
+            return Ok(());
+        }
+
+        let mut client = self.connected_client(name).await?;
+        info!("Waiting for apprentice {} to finish its spells", name);
+        tokio::time::timeout(DRAIN_TIMEOUT, async {
+            loop {
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                let status = client
+                    .get_status(tonic::Request::new(StatusRequest {}))
+                    .await?
+                    .into_inner();
+                if status.in_flight == 0 {
+                    return Ok::<_, anyhow::Error>(());
+                }
+            }
+        })
+        .await
+        .map_err(|_| {
+            anyhow!(
+                "Apprentice {} was still casting after {}s of draining",
+                name,
+                DRAIN_TIMEOUT.as_secs()
+            )
+        })?
+    }
+
+    /// Time a round trip to an apprentice over gRPC, without involving Claude.
+    pub async fn ping(&self, name: &str) -> Result<std::time::Duration> {
+        let mut client = self.connected_client(name).await?;
+
+        let start = std::time::Instant::now();
+        client.ping(tonic::Request::new(PingRequest {})).await?;
+        Ok(start.elapsed())
+    }
+
+    /// Abort the spell an apprentice is currently casting. Returns false if it
+    /// was idle.
+    pub async fn cancel_spell(&self, name: &str) -> Result<bool> {
+        let mut client = self.connected_client(name).await?;
+
+        let response = client
+            .cancel_spell(tonic::Request::new(CancelSpellRequest {}))
+            .await?;
 
-        Ok(chat_response.history)
+        Ok(response.into_inner().cancelled)
     }
 }
@@ -0,0 +1,82 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Most turns a transcript may hold; apprentices keep no more history than this.
+pub const MAX_TRANSCRIPT_TURNS: usize = 100;
+
+/// Largest transcript file accepted, in bytes.
+pub const MAX_TRANSCRIPT_BYTES: u64 = 1024 * 1024;
+
+/// A prior conversation to seed a new apprentice with, loaded by
+/// `srcrr summon --seed-history`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Transcript {
+    pub turns: Vec<TranscriptTurn>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TranscriptTurn {
+    /// "user" or "assistant"
+    pub role: String,
+    pub content: String,
+}
+
+impl Transcript {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let size = std::fs::metadata(path)
+            .with_context(|| format!("Failed to read transcript {}", path.display()))?
+            .len();
+        if size > MAX_TRANSCRIPT_BYTES {
+            return Err(anyhow!(
+                "Transcript {} is {} bytes; the limit is {}",
+                path.display(),
+                size,
+                MAX_TRANSCRIPT_BYTES
+            ));
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read transcript {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("Invalid transcript {}", path.display()))
+    }
+
+    /// Parse a transcript and check it can be replayed to Claude: turns must
+    /// alternate user/assistant, starting with the user and ending with a reply.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let transcript: Self = serde_json::from_str(contents)?;
+
+        if transcript.turns.is_empty() {
+            return Err(anyhow!("Transcript has no turns"));
+        }
+        if transcript.turns.len() > MAX_TRANSCRIPT_TURNS {
+            return Err(anyhow!(
+                "Transcript has {} turns; the limit is {}",
+                transcript.turns.len(),
+                MAX_TRANSCRIPT_TURNS
+            ));
+        }
+        for (i, turn) in transcript.turns.iter().enumerate() {
+            let expected = if i % 2 == 0 { "user" } else { "assistant" };
+            if turn.role != expected {
+                return Err(anyhow!(
+                    "Turn {} has role {:?}; expected {:?} (turns alternate user/assistant)",
+                    i + 1,
+                    turn.role,
+                    expected
+                ));
+            }
+        }
+        if transcript
+            .turns
+            .last()
+            .is_some_and(|turn| turn.role != "assistant")
+        {
+            return Err(anyhow!("Transcript must end with an assistant turn"));
+        }
+
+        Ok(transcript)
+    }
+}
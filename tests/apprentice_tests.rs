@@ -310,3 +310,412 @@ mod apprentice_tests {
         }
     }
 }
+
+#[path = "../apprentice/src/prompt.rs"]
+mod prompt;
+
+#[cfg(test)]
+mod prompt_tests {
+    use super::prompt::{render, PromptVars};
+
+    const VARS: PromptVars = PromptVars {
+        name: "mickey",
+        date: "2024-05-01",
+        workspace: "/workspace",
+    };
+
+    #[test]
+    fn test_render_substitutes_known_placeholders() {
+        let rendered = render(
+            "You are {{name}}. Today is {{date}}. Work in {{workspace}}; {{name}} again.",
+            &VARS,
+        );
+        assert_eq!(
+            rendered,
+            "You are mickey. Today is 2024-05-01. Work in /workspace; mickey again."
+        );
+    }
+
+    #[test]
+    fn test_render_leaves_unknown_placeholders() {
+        assert_eq!(
+            render("{{unknown}} and {{ name }} and {{name", &VARS),
+            "{{unknown}} and {{ name }} and {{name"
+        );
+        assert_eq!(render("no placeholders", &VARS), "no placeholders");
+    }
+
+    #[test]
+    fn test_render_does_not_expand_substituted_values() {
+        let vars = PromptVars {
+            name: "{{date}}",
+            ..VARS
+        };
+        assert_eq!(render("{{name}}", &vars), "{{date}}");
+    }
+}
+
+#[path = "../apprentice/src/claude.rs"]
+#[allow(dead_code)]
+mod claude;
+
+#[cfg(test)]
+mod claude_tests {
+    use super::claude::{ClaudeClient, Role};
+    use serial_test::serial;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Answer one request like the Messages API would and return its JSON body.
+    async fn capture_request(listener: TcpListener) -> serde_json::Value {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let body = read_request(&mut socket).await;
+        write_reply(&mut socket).await;
+        body
+    }
+
+    /// Read one HTTP request and return its JSON body.
+    pub(crate) async fn read_request(socket: &mut TcpStream) -> serde_json::Value {
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 4096];
+        let body_start = loop {
+            let n = socket.read(&mut chunk).await.unwrap();
+            request.extend_from_slice(&chunk[..n]);
+            if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                break end + 4;
+            }
+        };
+        let headers = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+        let length: usize = headers
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length:"))
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        while request.len() < body_start + length {
+            let n = socket.read(&mut chunk).await.unwrap();
+            request.extend_from_slice(&chunk[..n]);
+        }
+        serde_json::from_slice(&request[body_start..]).unwrap()
+    }
+
+    /// Reply like the Messages API would to a successful request.
+    pub(crate) async fn write_reply(socket: &mut TcpStream) {
+        let reply = r#"{"content":[{"type":"text","text":"8"}],"usage":{"input_tokens":20,"output_tokens":1}}"#;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            reply.len(),
+            reply
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_send_message_replays_prior_turns() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        std::env::set_var(
+            "ANTHROPIC_BASE_URL",
+            format!("http://{}", listener.local_addr().unwrap()),
+        );
+        std::env::set_var("ANTHROPIC_API_KEY", "test-key");
+        std::env::remove_var("ANTHROPIC_API_KEY_FILE");
+        std::env::remove_var("MAX_REPLAY_TURNS");
+        let client = ClaudeClient::new();
+        std::env::remove_var("ANTHROPIC_BASE_URL");
+
+        let server = tokio::spawn(capture_request(listener));
+        let history = vec![
+            (Role::User, "What is 2 + 2?".to_string()),
+            (Role::Assistant, "4".to_string()),
+        ];
+        let reply = client
+            .send_message(&history, "And doubled?", None, None)
+            .await
+            .unwrap();
+        let body = server.await.unwrap();
+
+        assert_eq!(reply.text, "8");
+        assert_eq!(
+            body["messages"],
+            serde_json::json!([
+                {"role": "user", "content": "What is 2 + 2?"},
+                {"role": "assistant", "content": "4"},
+                {"role": "user", "content": "And doubled?"},
+            ])
+        );
+    }
+}
+
+#[path = "../apprentice/src/rate_limit.rs"]
+mod rate_limit;
+
+#[path = "../apprentice/src/text.rs"]
+mod text;
+
+#[path = "../apprentice/src/server.rs"]
+#[allow(dead_code)]
+mod server;
+
+#[cfg(test)]
+mod server_tests {
+    use super::claude_tests::{read_request, write_reply};
+    use super::server::spells::apprentice_server::Apprentice;
+    use super::server::spells::{
+        AppendHistoryRequest, CancelSpellRequest, ChatHistoryRequest, DrainRequest, HistoryTurn,
+        SpellRequest, SpellResponse, StatusRequest, StatusResponse,
+    };
+    use super::server::{truncate_response, ApprenticeServer};
+    use serial_test::serial;
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tokio::sync::{mpsc, oneshot};
+    use tokio::task::JoinHandle;
+    use tonic::{Code, Request};
+
+    fn apprentice() -> ApprenticeServer {
+        for var in [
+            "SPELL_RATE_LIMIT",
+            "SYSTEM_PROMPT_PATH",
+            "SYSTEM_PROMPT_APPEND",
+            "AGENT_COMPACT_HISTORY",
+        ] {
+            std::env::remove_var(var);
+        }
+        ApprenticeServer::new("mickey".to_string())
+    }
+
+    /// An apprentice whose Claude requests are held until the test releases
+    /// them. Each request that arrives yields a sender that answers it.
+    async fn apprentice_with_held_claude(
+    ) -> (Arc<ApprenticeServer>, mpsc::Receiver<oneshot::Sender<()>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        std::env::set_var(
+            "ANTHROPIC_BASE_URL",
+            format!("http://{}", listener.local_addr().unwrap()),
+        );
+        std::env::set_var("ANTHROPIC_API_KEY", "test-key");
+        std::env::remove_var("ANTHROPIC_API_KEY_FILE");
+        let apprentice = Arc::new(apprentice());
+        std::env::remove_var("ANTHROPIC_BASE_URL");
+
+        let (held_tx, held_rx) = mpsc::channel(8);
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let held_tx = held_tx.clone();
+                tokio::spawn(async move {
+                    read_request(&mut socket).await;
+                    let (release_tx, release_rx) = oneshot::channel();
+                    held_tx.send(release_tx).await.unwrap();
+                    if release_rx.await.is_ok() {
+                        write_reply(&mut socket).await;
+                    }
+                });
+            }
+        });
+        (apprentice, held_rx)
+    }
+
+    fn cast(apprentice: &Arc<ApprenticeServer>, spell_id: &str) -> JoinHandle<SpellResponse> {
+        let apprentice = apprentice.clone();
+        let spell_id = spell_id.to_string();
+        tokio::spawn(async move {
+            apprentice
+                .cast_spell(Request::new(SpellRequest {
+                    incantation: "What is 4 + 4?".to_string(),
+                    spell_id,
+                    ..Default::default()
+                }))
+                .await
+                .unwrap()
+                .into_inner()
+        })
+    }
+
+    async fn status(apprentice: &ApprenticeServer) -> StatusResponse {
+        apprentice
+            .get_status(Request::new(StatusRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+    }
+
+    async fn cancel(apprentice: &ApprenticeServer) -> bool {
+        apprentice
+            .cancel_spell(Request::new(CancelSpellRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .cancelled
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_in_flight_count_and_cancel_handle() {
+        let (apprentice, mut held) = apprentice_with_held_claude().await;
+
+        let first = cast(&apprentice, "first");
+        let release_first = held.recv().await.unwrap();
+        let second = cast(&apprentice, "second");
+        let _release_second = held.recv().await.unwrap();
+        let casting = status(&apprentice).await;
+        assert_eq!((casting.state.as_str(), casting.in_flight), ("casting", 2));
+
+        // The first spell finishing must not release the second's cancel handle
+        release_first.send(()).unwrap();
+        assert!(first.await.unwrap().success);
+        let casting = status(&apprentice).await;
+        assert_eq!((casting.state.as_str(), casting.in_flight), ("casting", 1));
+
+        assert!(cancel(&apprentice).await);
+        assert_eq!(second.await.unwrap().error, "cancelled");
+        let idle = status(&apprentice).await;
+        assert_eq!((idle.state.as_str(), idle.in_flight), ("idle", 0));
+        assert!(!cancel(&apprentice).await);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_drain_refuses_new_spells_while_in_flight_ones_finish() {
+        let (apprentice, mut held) = apprentice_with_held_claude().await;
+
+        let first = cast(&apprentice, "first");
+        let release_first = held.recv().await.unwrap();
+        let drain = apprentice
+            .drain(Request::new(DrainRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(drain.in_flight, 1);
+
+        let refused = cast(&apprentice, "refused").await.unwrap();
+        assert!(!refused.success);
+        assert!(refused.error.starts_with("draining"));
+        let draining = status(&apprentice).await;
+        assert!(draining.draining);
+        assert_eq!(draining.in_flight, 1);
+
+        release_first.send(()).unwrap();
+        assert!(first.await.unwrap().success);
+        let drained = status(&apprentice).await;
+        assert_eq!((drained.state.as_str(), drained.in_flight), ("idle", 0));
+    }
+
+    /// `count` alternating turns, starting with the user.
+    fn turns(count: usize) -> Vec<HistoryTurn> {
+        (0..count)
+            .map(|i| HistoryTurn {
+                role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
+                content: format!("turn {i}"),
+                timestamp: String::new(),
+            })
+            .collect()
+    }
+
+    async fn append(apprentice: &ApprenticeServer, turns: Vec<HistoryTurn>) -> Result<u32, Code> {
+        apprentice
+            .append_history(Request::new(AppendHistoryRequest {
+                session_id: String::new(),
+                turns,
+            }))
+            .await
+            .map(|response| response.into_inner().history_len)
+            .map_err(|status| status.code())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_append_history_rejects_non_alternating_turns() {
+        let apprentice = apprentice();
+        let mut doubled = turns(4);
+        doubled[1].role = "user".to_string();
+        assert_eq!(
+            append(&apprentice, doubled).await,
+            Err(Code::InvalidArgument)
+        );
+
+        // The existing tail counts too: after an assistant turn, the next must be the user's
+        assert_eq!(append(&apprentice, turns(2)).await, Ok(2));
+        let mut from_assistant = turns(3);
+        from_assistant.remove(0);
+        assert_eq!(
+            append(&apprentice, from_assistant).await,
+            Err(Code::InvalidArgument)
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_append_history_rejects_ending_on_user() {
+        let apprentice = apprentice();
+        assert_eq!(
+            append(&apprentice, turns(3)).await,
+            Err(Code::InvalidArgument)
+        );
+        assert_eq!(append(&apprentice, turns(2)).await, Ok(2));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_append_history_trims_to_a_user_turn() {
+        let apprentice = apprentice();
+        assert_eq!(append(&apprentice, turns(60)).await, Ok(60));
+        assert_eq!(append(&apprentice, turns(62)).await, Ok(100));
+
+        let history = apprentice
+            .get_chat_history(Request::new(ChatHistoryRequest {
+                lines: 0,
+                session_id: String::new(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .entries;
+        assert_eq!(history.len(), 100);
+        assert_eq!(history[0].role, "user");
+        assert_eq!(history[99].content, "turn 61");
+    }
+
+    #[test]
+    fn test_truncate_response_multibyte() {
+        let reply = "日本語のテキスト".to_string();
+        assert_eq!(
+            truncate_response(reply.clone(), 3),
+            "日本語…[truncated 5 chars]"
+        );
+        assert_eq!(truncate_response(reply.clone(), 8), reply);
+        assert_eq!(
+            truncate_response("🧙‍♂️ casts".to_string(), 1),
+            "🧙…[truncated 9 chars]"
+        );
+    }
+}
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use super::rate_limit::RateLimiter;
+    use std::time::Duration;
+
+    #[test]
+    fn test_rate_limiter_denies_when_empty() {
+        let mut limiter = RateLimiter::per_minute(2);
+        assert_eq!(limiter.remaining(), 2);
+        assert!(limiter.try_acquire());
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+        assert_eq!(limiter.remaining(), 0);
+    }
+
+    #[test]
+    fn test_rate_limiter_refills_over_time() {
+        // 100 spells a second, so a short sleep refills a few
+        let mut limiter = RateLimiter::per_minute(6000);
+        while limiter.try_acquire() {}
+        assert!(!limiter.try_acquire());
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(limiter.try_acquire());
+    }
+}
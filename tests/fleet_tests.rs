@@ -0,0 +1,96 @@
+use sorcerer::fleet::{parse_memory, FleetSpec};
+
+#[cfg(test)]
+mod fleet_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yaml_spec() {
+        let spec = FleetSpec::parse(
+            "apprentices:\n  - name: mickey\n    model: claude-3-5-haiku-latest\n    memory: 512m\n  - name: yen_sid\n",
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(spec.apprentices.len(), 2);
+        assert_eq!(spec.apprentices[0].name, "mickey");
+        assert_eq!(
+            spec.apprentices[0].model.as_deref(),
+            Some("claude-3-5-haiku-latest")
+        );
+        assert_eq!(spec.apprentices[1].memory, None);
+    }
+
+    #[test]
+    fn test_parse_toml_spec() {
+        let spec =
+            FleetSpec::parse("[[apprentices]]\nname = \"mickey\"\ncpus = 1.5\n", true).unwrap();
+
+        assert_eq!(spec.apprentices.len(), 1);
+        assert_eq!(spec.apprentices[0].cpus, Some(1.5));
+    }
+
+    #[test]
+    fn test_spec_rejects_duplicates_and_unknown_fields() {
+        assert!(FleetSpec::parse("apprentices:\n  - name: a\n  - name: a\n", false).is_err());
+        assert!(FleetSpec::parse("apprentices:\n  - name: a\n    colour: red\n", false).is_err());
+    }
+
+    #[test]
+    fn test_spec_rejects_invalid_cpus() {
+        for cpus in ["0", "-1.5", "nan", "inf"] {
+            let spec = format!("[[apprentices]]\nname = \"mickey\"\ncpus = {cpus}\n");
+            assert!(FleetSpec::parse(&spec, true).is_err(), "cpus = {cpus}");
+        }
+    }
+
+    #[test]
+    fn test_summon_options_mount_prompt_and_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("prompt.md"), "You are Carol.").unwrap();
+        std::fs::create_dir(dir.path().join("project")).unwrap();
+        let base_dir = dir.path().canonicalize().unwrap();
+
+        let spec = FleetSpec::parse(
+            "apprentices:\n  - name: carol\n    memory: 1g\n    prompt: prompt.md\n    workspace: project\n",
+            false,
+        )
+        .unwrap();
+        let options = spec.apprentices[0].summon_options(&base_dir).unwrap();
+
+        assert_eq!(options.memory, Some(1024 * 1024 * 1024));
+        assert_eq!(
+            options.mounts,
+            vec![
+                format!(
+                    "{}:/etc/apprentice/prompt.md:ro",
+                    base_dir.join("prompt.md").display()
+                ),
+                format!("{}:/workspace", base_dir.join("project").display()),
+            ]
+        );
+        assert!(options
+            .env
+            .contains(&"SYSTEM_PROMPT_PATH=/etc/apprentice/prompt.md".to_string()));
+        assert!(options.env.contains(&"WORKSPACE=/workspace".to_string()));
+
+        let missing = FleetSpec::parse(
+            "apprentices:\n  - name: carol\n    prompt: nope.md\n",
+            false,
+        )
+        .unwrap();
+        assert!(missing.apprentices[0].summon_options(&base_dir).is_err());
+    }
+
+    #[test]
+    fn test_parse_memory() {
+        assert_eq!(parse_memory("1024").unwrap(), 1024);
+        assert_eq!(parse_memory("4k").unwrap(), 4096);
+        assert_eq!(parse_memory("512m").unwrap(), 512 * 1024 * 1024);
+        assert_eq!(parse_memory("2G").unwrap(), 2 * 1024 * 1024 * 1024);
+        assert!(parse_memory("").is_err());
+        assert!(parse_memory("lots").is_err());
+        assert!(parse_memory("-5m").is_err());
+        assert!(parse_memory("9223372036854775807g").is_err());
+    }
+}
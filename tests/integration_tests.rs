@@ -67,6 +67,39 @@ fn test_help_flag() {
     ));
 }
 
+#[test]
+fn test_quiet_flag_in_help() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--quiet"));
+}
+
+#[test]
+#[serial]
+fn test_list_quiet_omits_header() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.args(["--quiet", "list"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("📋 Listing apprentices...").not());
+}
+
+#[test]
+#[serial]
+fn test_ls_quiet_prints_bare_names() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.args(["ls", "-q"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("🧙").not())
+        .stdout(predicate::str::contains("The realm is empty").not());
+}
+
 #[test]
 fn test_invalid_command() {
     let mut cmd = Command::cargo_bin("srcrr").unwrap();
@@ -183,13 +216,29 @@ fn test_kill_nonexistent_apprentice() {
     cmd.args(["kill", "nonexistent_apprentice"]);
 
     cmd.assert()
-        .success()
+        .failure()
         .stdout(predicate::str::contains(
             "💀 Killing apprentice nonexistent_apprentice...",
         ))
         .stdout(predicate::str::contains("⚠️  Kill failed"));
 }
 
+#[test]
+#[serial]
+fn test_kill_nonexistent_apprentice_json() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.args(["kill", "nonexistent_apprentice", "--json"]);
+
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(stdout.trim()).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(summary["requested"], 1);
+    assert_eq!(summary["succeeded"], 0);
+    assert_eq!(summary["failed"][0]["name"], "nonexistent_apprentice");
+}
+
 #[test]
 fn test_overview_invalid_lines_option() {
     let mut cmd = Command::cargo_bin("srcrr").unwrap();
@@ -319,6 +368,36 @@ fn test_history_with_lines_option() {
         .stdout(predicate::str::contains("📜 Viewing chat history"));
 }
 
+#[test]
+fn test_sessions_command_validation() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.arg("sessions");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("required"));
+}
+
+#[test]
+fn test_summon_rejects_malformed_env() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.args(["summon", "test-apprentice", "--env", "NO_EQUALS_SIGN"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("KEY=VALUE"));
+}
+
+#[test]
+fn test_summon_interval_requires_wait_healthy() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.args(["summon", "test-apprentice", "--interval", "100"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--wait-healthy"));
+}
+
 #[test]
 fn test_history_command_validation() {
     // Test without apprentice name - should fail
@@ -329,3 +408,60 @@ fn test_history_command_validation() {
         .failure()
         .stderr(predicate::str::contains("required"));
 }
+
+#[test]
+fn test_tell_rejects_unknown_output_format() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.args(["tell", "test-apprentice", "hello", "--output", "yaml"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("possible values: text, json"));
+}
+
+#[test]
+fn test_tell_plain_conflicts_with_output() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.args([
+        "tell",
+        "test-apprentice",
+        "hello",
+        "--plain",
+        "--output",
+        "json",
+    ]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_kill_drain_conflicts_with_force() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.args(["kill", "test-apprentice", "--drain", "--force"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_verbose_conflicts_with_quiet() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.args(["--verbose", "--quiet", "list"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_follow_requires_names_or_all() {
+    let mut cmd = Command::cargo_bin("srcrr").unwrap();
+    cmd.arg("follow");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("required"));
+}
@@ -1,3 +1,5 @@
+use sorcerer::config::Config;
+use sorcerer::{is_connection_error, pinned_digest};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -281,4 +283,56 @@ mod sorcerer_tests {
             assert!(valid_states.contains(state));
         }
     }
+
+    #[test]
+    fn test_only_undelivered_spells_are_retried() {
+        let unavailable = anyhow::Error::new(tonic::Status::unavailable("connection refused"));
+        assert!(is_connection_error(&unavailable));
+        assert!(is_connection_error(
+            &unavailable.context("Failed to cast spell")
+        ));
+
+        for status in [
+            tonic::Status::cancelled("stream reset"),
+            tonic::Status::unknown("h2 protocol error"),
+            tonic::Status::internal("Claude API error"),
+        ] {
+            assert!(!is_connection_error(&anyhow::Error::new(status)));
+        }
+    }
+
+    #[test]
+    fn test_pinned_digest() {
+        let digest = "sha256:3f1e4a9c0b7d2e6f8a5c1b0d9e7f6a5b4c3d2e1f0a9b8c7d6e5f4a3b2c1d0e9f";
+        assert_eq!(
+            pinned_digest(&format!("sorcerer-apprentice@{digest}")),
+            Some(digest)
+        );
+        assert_eq!(
+            pinned_digest(&format!(
+                "registry.example.com:5000/sorcerer-apprentice@{digest}"
+            )),
+            Some(digest)
+        );
+        assert_eq!(pinned_digest("sorcerer-apprentice:latest"), None);
+        assert_eq!(pinned_digest("sorcerer-apprentice"), None);
+    }
+
+    #[test]
+    fn test_partial_tls_config_is_rejected() {
+        let tls = |cert: Option<&str>, key: Option<&str>, ca: Option<&str>| Config {
+            tls_cert: cert.map(String::from),
+            tls_key: key.map(String::from),
+            tls_ca: ca.map(String::from),
+            ..Default::default()
+        };
+
+        assert!(tls(None, None, None).check_tls().is_ok());
+        assert!(tls(Some("s.crt"), Some("s.key"), Some("s.crt"))
+            .check_tls()
+            .is_ok());
+        assert!(tls(Some("s.crt"), None, Some("s.crt")).check_tls().is_err());
+        assert!(tls(None, Some("s.key"), None).check_tls().is_err());
+        assert!(tls(None, None, Some("ca.crt")).check_tls().is_err());
+    }
 }
@@ -0,0 +1,39 @@
+use sorcerer::text::truncate_on_char_boundary;
+
+#[cfg(test)]
+mod text_tests {
+    use super::*;
+
+    const UNICODE: &str = "🧙‍♂️ 日本語 العربية";
+
+    #[test]
+    fn test_truncate_short_string_is_unchanged() {
+        assert_eq!(truncate_on_char_boundary("hello", 10), "hello");
+        assert_eq!(truncate_on_char_boundary("hello", 5), "hello");
+        assert_eq!(truncate_on_char_boundary("", 3), "");
+    }
+
+    #[test]
+    fn test_truncate_ascii() {
+        assert_eq!(truncate_on_char_boundary("hello world", 5), "hello");
+        assert_eq!(truncate_on_char_boundary("hello", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_multibyte_at_every_length() {
+        let total = UNICODE.chars().count();
+        for max in 0..=total + 1 {
+            let cut = truncate_on_char_boundary(UNICODE, max);
+            assert_eq!(cut.chars().count(), max.min(total));
+            assert!(UNICODE.starts_with(cut));
+        }
+    }
+
+    #[test]
+    fn test_truncate_splits_grapheme_by_chars() {
+        // The wizard emoji is a ZWJ sequence of four chars
+        assert_eq!(truncate_on_char_boundary(UNICODE, 1), "🧙");
+        assert_eq!(truncate_on_char_boundary(UNICODE, 4), "🧙‍♂️");
+        assert_eq!(truncate_on_char_boundary(UNICODE, 8), "🧙‍♂️ 日本語");
+    }
+}
@@ -0,0 +1,67 @@
+use sorcerer::transcript::{Transcript, MAX_TRANSCRIPT_TURNS};
+
+#[cfg(test)]
+mod transcript_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_valid_transcript() {
+        let transcript = Transcript::parse(
+            r#"{"turns": [
+                {"role": "user", "content": "What is 2 + 2?"},
+                {"role": "assistant", "content": "4"}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(transcript.turns.len(), 2);
+        assert_eq!(transcript.turns[0].role, "user");
+        assert_eq!(transcript.turns[1].content, "4");
+    }
+
+    #[test]
+    fn test_parse_rejects_empty_transcript() {
+        assert!(Transcript::parse(r#"{"turns": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_out_of_order_roles() {
+        let err = Transcript::parse(
+            r#"{"turns": [
+                {"role": "assistant", "content": "Hello"},
+                {"role": "user", "content": "Hi"}
+            ]}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Turn 1"));
+    }
+
+    #[test]
+    fn test_parse_rejects_trailing_user_turn() {
+        let err =
+            Transcript::parse(r#"{"turns": [{"role": "user", "content": "Hi"}]}"#).unwrap_err();
+        assert!(err.to_string().contains("assistant"));
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_fields() {
+        assert!(Transcript::parse(
+            r#"{"turns": [{"role": "user", "content": "Hi", "time": "now"}]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_too_many_turns() {
+        let turns: Vec<String> = (0..MAX_TRANSCRIPT_TURNS + 2)
+            .map(|i| {
+                let role = if i % 2 == 0 { "user" } else { "assistant" };
+                format!(r#"{{"role": "{role}", "content": "turn {i}"}}"#)
+            })
+            .collect();
+        let contents = format!(r#"{{"turns": [{}]}}"#, turns.join(","));
+
+        let err = Transcript::parse(&contents).unwrap_err();
+        assert!(err.to_string().contains("limit"));
+    }
+}